@@ -1,3 +1,53 @@
+use minisign_verify::PublicKey;
+
+// Khóa công khai minisign nhúng vào app: file `.pub` của dự án trong `keys/` (do `minisign -G` tạo)
+// và khóa thêm qua biến môi trường (nhiều khóa cách nhau bởi dấu phẩy, dùng khi xoay vòng khóa).
+// (file, biến môi trường, biến đọc bằng env! trong signature_service)
+const KEY_SETS: [(&str, &str, &str); 2] = [
+    ("keys/priconevh.pub", "PRICONEVH_SIGNING_KEYS", "PRICONEVH_TRUSTED_SIGNING_KEYS"),
+    ("keys/priconevh-updater.pub", "PRICONEVH_UPDATER_KEYS", "PRICONEVH_TRUSTED_UPDATER_KEYS"),
+];
+
 fn main() {
+    let release = std::env::var("PROFILE").as_deref() == Ok("release");
+    let mut key_sets = Vec::new();
+
+    for (file, env_name, output) in KEY_SETS {
+        println!("cargo:rerun-if-changed={}", file);
+        println!("cargo:rerun-if-env-changed={}", env_name);
+
+        let mut keys = Vec::new();
+        if let Ok(content) = std::fs::read_to_string(file) {
+            // Dòng đầu là `untrusted comment: ...`, khóa ở dòng thứ hai
+            let key = content.lines().nth(1).unwrap_or_default().trim().to_string();
+            keys.push(key);
+        }
+        if let Ok(extra) = std::env::var(env_name) {
+            keys.extend(extra.split(',').map(str::trim).filter(|key| !key.is_empty()).map(String::from));
+        }
+
+        for key in &keys {
+            if let Err(e) = PublicKey::from_base64(key) {
+                panic!("Invalid minisign public key in {} or {}: {} ({})", file, env_name, key, e);
+            }
+        }
+
+        // Bản debug (test, chạy thử) vẫn build được, chỉ cài được bản chưa ký trong chế độ nhà phát triển
+        if keys.is_empty() {
+            if release {
+                panic!("No signing key configured: commit {} or set {} (see UPDATER_SETUP.md)", file, env_name);
+            }
+            println!("cargo:warning=No key in {} or {}, signed downloads will be rejected", file, env_name);
+        }
+
+        println!("cargo:rustc-env={}={}", output, keys.join(","));
+        key_sets.push(keys);
+    }
+
+    // Khóa ký bản việt hóa không được ký file cài đặt app
+    if let Some(key) = key_sets[0].iter().find(|key| key_sets[1].contains(key)) {
+        panic!("The same key is used for translation packs and app updates: {}", key);
+    }
+
     tauri_build::build()
 }
@@ -0,0 +1,441 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, GameInfo, HistoryOperation, InstallResult, ProgressEvent, ProgressPhase};
+use crate::services::translation_service::TranslationInfo;
+use crate::services::{
+    create_release_provider, DeltaService, DoorstopService, GameService, HistoryRecorder,
+    LocaleService, LoggingService, ProgressReporter, TranslationService,
+};
+use crate::t;
+use clap::{CommandFactory, Parser, Subcommand};
+use serde::Serialize;
+use std::path::PathBuf;
+
+const EXIT_OK: i32 = 0;
+const EXIT_ERROR: i32 = 1;
+// Lệnh chạy xong nhưng kết quả không đạt (không tìm thấy game, file bị thiếu/sửa)
+const EXIT_CHECK_FAILED: i32 = 3;
+
+/// Quản lý bản việt hóa Princess Connect Re:Dive từ dòng lệnh
+#[derive(Parser)]
+#[command(name = "priconevh", version)]
+struct Cli {
+    /// Thư mục game (mặc định lấy từ cài đặt hoặc tự tìm)
+    #[arg(long, global = true)]
+    game_path: Option<PathBuf>,
+
+    /// In kết quả dạng JSON
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Tìm thư mục game mặc định của DMM
+    Detect,
+    /// Xem trạng thái game và bản việt hóa
+    Status,
+    /// Liệt kê các phiên bản việt hóa
+    List,
+    /// Cài đặt một phiên bản (mặc định là bản mới nhất) hoặc file nén trên máy
+    Install {
+        /// Phiên bản cần cài, khi dùng --zip thì là tên phiên bản ghi lại
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Cài từ file nén (zip, 7z, tar.zst) trên máy thay vì tải về
+        #[arg(long)]
+        zip: Option<PathBuf>,
+    },
+    /// Cập nhật lên phiên bản mới nhất
+    Update,
+    /// Gỡ bản việt hóa (có sao lưu)
+    Uninstall,
+    /// Kiểm tra file bản việt hóa so với lúc cài
+    Verify,
+    /// Khôi phục bản việt hóa từ bản sao lưu
+    Restore,
+}
+
+/// Chạy dạng dòng lệnh nếu có subcommand, trả về exit code. Không có thì trả về None để mở GUI
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    let first = args.get(1)?;
+
+    let is_cli = matches!(first.as_str(), "help" | "-h" | "--help" | "-V" | "--version" | "--json")
+        || first.starts_with("--game-path")
+        || Cli::command().get_subcommands().any(|sub| sub.get_name() == first);
+    if !is_cli {
+        return None;
+    }
+
+    attach_console();
+
+    let cli = match Cli::try_parse_from(&args) {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return Some(e.exit_code());
+        }
+    };
+
+    Some(run(cli))
+}
+
+fn run(cli: Cli) -> i32 {
+    let config = AppConfig::load().unwrap_or_default();
+    LocaleService::set_language(&config.language);
+    if let Err(e) = LoggingService::init(&config.log_level) {
+        LoggingService::init_stderr(&config.log_level);
+        tracing::warn!(error = %e, "File logging unavailable, logging to stderr");
+    }
+
+    let output = Output { json: cli.json };
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            output.error(&err!("error.async_runtime", error = e));
+            return EXIT_ERROR;
+        }
+    };
+
+    match runtime.block_on(execute(&cli, &config, &output)) {
+        Ok(code) => code,
+        Err(e) => {
+            tracing::error!(error = %e, "CLI command failed");
+            output.error(&e);
+            EXIT_ERROR
+        }
+    }
+}
+
+#[tracing::instrument(name = "cli", skip_all)]
+async fn execute(cli: &Cli, config: &AppConfig, output: &Output) -> Result<i32, AppError> {
+    let service = TranslationService::new(create_release_provider(config));
+
+    match &cli.command {
+        Command::Detect => {
+            let game_path = GameService::auto_detect_game_path();
+            output.print(&serde_json::json!({ "game_path": game_path }), || match &game_path {
+                Some(path) => path.display().to_string(),
+                None => t!("cli.game_not_found"),
+            });
+
+            Ok(if game_path.is_some() { EXIT_OK } else { EXIT_CHECK_FAILED })
+        }
+        Command::Status => {
+            let game_info = resolve_game(cli, config)?;
+            let translation = TranslationInfo::load(&game_info.path);
+            let game_running = GameService::is_game_running();
+
+            output.print(
+                &serde_json::json!({
+                    "game": game_info,
+                    "translation": translation,
+                    "game_running": game_running,
+                }),
+                || {
+                    let translation = match &translation {
+                        Some(info) if game_info.translation_enabled => info.version.clone(),
+                        Some(info) => t!("cli.version_disabled", version = info.version),
+                        None => t!("cli.not_installed"),
+                    };
+                    t!(
+                        "cli.status",
+                        path = game_info.path.display(),
+                        game_version = game_info.version.clone().unwrap_or_else(|| t!("cli.unknown")),
+                        translation = translation,
+                        running = if game_running { t!("cli.yes") } else { t!("cli.no") },
+                    )
+                },
+            );
+
+            Ok(EXIT_OK)
+        }
+        Command::List => {
+            let pack = service.get_translation_pack().await?;
+            let installed = resolve_game(cli, config)
+                .ok()
+                .and_then(|game_info| TranslationInfo::load(&game_info.path))
+                .map(|info| info.version);
+
+            output.print(&pack, || {
+                pack.versions
+                    .iter()
+                    .map(|version| {
+                        let mark = if installed.as_deref() == Some(version.version.as_str()) {
+                            t!("cli.installed_mark")
+                        } else {
+                            String::new()
+                        };
+                        format!(
+                            "{}\t{}\t{:.1} MB{}",
+                            version.version,
+                            version.release_date,
+                            version.file_size as f64 / 1_048_576.0,
+                            mark,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+
+            Ok(EXIT_OK)
+        }
+        Command::Install { version, zip } => {
+            let game_info = resolve_game(cli, config)?;
+            let from_version = TranslationInfo::load(&game_info.path).map(|info| info.version);
+
+            let result = match zip {
+                Some(zip_path) => {
+                    let version = version.clone().unwrap_or_else(|| {
+                        // "PriconneTL_x.tar.zst" -> "PriconneTL_x"
+                        zip_path.file_stem()
+                            .map(|stem| stem.to_string_lossy().trim_end_matches(".tar").to_string())
+                            .unwrap_or_else(|| "local".to_string())
+                    });
+                    let history = HistoryRecorder::start(
+                        HistoryOperation::Install,
+                        &game_info.path,
+                        from_version,
+                        Some(version.clone()),
+                    );
+
+                    let result = service.install_local_zip(&game_info, zip_path, &version, &output.progress("install"));
+                    history.finish(&result, 0);
+                    result?
+                }
+                None => {
+                    let pack = service.get_translation_pack().await?;
+                    let target = match version {
+                        Some(version) => pack.get_version(version)
+                            .ok_or_else(|| err!("error.version_not_found", version = version))?,
+                        None => pack.get_latest()
+                            .ok_or_else(|| err!("error.no_translation_versions"))?,
+                    };
+                    let history = HistoryRecorder::start(
+                        HistoryOperation::Install,
+                        &game_info.path,
+                        from_version,
+                        Some(target.version.clone()),
+                    );
+
+                    let result = service.install_translation(&game_info, target, &output.progress("install")).await;
+                    history.finish(&result, result.as_ref().map(|r| r.bytes_downloaded).unwrap_or(0));
+                    result?
+                }
+            };
+
+            output.install_result(&result);
+            Ok(EXIT_OK)
+        }
+        Command::Update => {
+            let game_info = resolve_game(cli, config)?;
+            let info = TranslationInfo::load(&game_info.path)
+                .ok_or_else(|| err!("error.translation_not_installed"))?;
+
+            let Some(target) = service.check_for_updates(&info.version).await? else {
+                output.print(&serde_json::json!({ "up_to_date": true, "version": info.version }), || {
+                    t!("cli.already_latest", version = info.version)
+                });
+                return Ok(EXIT_OK);
+            };
+
+            let history = HistoryRecorder::start(
+                HistoryOperation::Update,
+                &game_info.path,
+                Some(info.version.clone()),
+                Some(target.version.clone()),
+            );
+            let result = service.update_translation(&game_info, &target, &output.progress("update")).await;
+            history.finish(&result, result.as_ref().map(|r| r.bytes_downloaded).unwrap_or(0));
+
+            output.install_result(&result?);
+            Ok(EXIT_OK)
+        }
+        Command::Uninstall => {
+            let game_info = resolve_game(cli, config)?;
+            let from_version = TranslationInfo::load(&game_info.path).map(|info| info.version);
+            let history = HistoryRecorder::start(
+                HistoryOperation::Uninstall,
+                &game_info.path,
+                from_version,
+                None,
+            );
+
+            let result = service.uninstall_translation(&game_info.path);
+            history.finish(&result, 0);
+            result?;
+
+            output.print(&serde_json::json!({ "uninstalled": true }), || {
+                t!("cli.uninstalled")
+            });
+            Ok(EXIT_OK)
+        }
+        Command::Verify => {
+            let game_info = resolve_game(cli, config)?;
+            let report = DeltaService::verify_installed(&game_info.path)?;
+
+            output.print(&report, || {
+                let mut lines = vec![t!("cli.verify_summary", version = report.version, count = report.checked)];
+                lines.extend(report.missing.iter().map(|file| t!("cli.verify_missing", file = file)));
+                lines.extend(report.modified.iter().map(|file| t!("cli.verify_modified", file = file)));
+                if report.is_ok() {
+                    lines.push(t!("cli.verify_ok"));
+                }
+                lines.join("\n")
+            });
+
+            Ok(if report.is_ok() { EXIT_OK } else { EXIT_CHECK_FAILED })
+        }
+        Command::Restore => {
+            let game_info = resolve_game(cli, config)?;
+            let from_version = TranslationInfo::load(&game_info.path).map(|info| info.version);
+            let backup_version = TranslationInfo::load(&game_info.path.join("translation_backup"))
+                .map(|info| info.version);
+            let history = HistoryRecorder::start(
+                HistoryOperation::Restore,
+                &game_info.path,
+                from_version,
+                backup_version,
+            );
+
+            let result = service.restore_translation_backup(&game_info.path);
+            history.finish(&result, 0);
+            let restored = result?;
+
+            output.print(&serde_json::json!({ "restored_version": restored }), || match &restored {
+                Some(version) => t!("cli.restored_version", version = version),
+                None => t!("cli.restored"),
+            });
+            Ok(EXIT_OK)
+        }
+    }
+}
+
+/// Lấy thư mục game theo thứ tự: --game-path, cài đặt của app, tự tìm
+fn resolve_game(cli: &Cli, config: &AppConfig) -> Result<GameInfo, AppError> {
+    let game_path = cli.game_path.clone()
+        .or_else(|| config.game_path.clone())
+        .or_else(GameService::auto_detect_game_path)
+        .ok_or_else(|| t!("cli.game_path_not_set"))?;
+
+    let mut game_info = GameService::validate_game_path(game_path)?;
+    game_info.version = GameService::get_game_version(&game_info.path);
+    game_info.translation_enabled = game_info.has_translation && DoorstopService::is_enabled(&game_info.path);
+
+    Ok(game_info)
+}
+
+struct Output {
+    json: bool,
+}
+
+impl Output {
+    /// JSON ra stdout khi có --json, ngược lại in dạng văn bản
+    fn print<T: Serialize>(&self, value: &T, text: impl FnOnce() -> String) {
+        if self.json {
+            match serde_json::to_string_pretty(value) {
+                Ok(content) => println!("{}", content),
+                Err(e) => eprintln!("{}", err!("error.serialize_output", error = e)),
+            }
+        } else {
+            println!("{}", text());
+        }
+    }
+
+    fn error(&self, error: &AppError) {
+        if self.json {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "error": error.message(),
+                    "code": error.code(),
+                })
+            );
+        }
+        eprintln!("{}", t!("cli.error", message = error));
+    }
+
+    /// Tiến trình in ra stderr để không lẫn với kết quả
+    fn progress(&self, kind: &str) -> ProgressReporter {
+        if self.json {
+            return ProgressReporter::silent();
+        }
+
+        ProgressReporter::new(ProgressReporter::new_operation_id(kind), |event| {
+            eprintln!("{}", Self::progress_line(event));
+        })
+    }
+
+    fn progress_line(event: &ProgressEvent) -> String {
+        let label = match event.phase {
+            ProgressPhase::Preparing => "progress.preparing",
+            ProgressPhase::CheckingChanges => "progress.checking_changes",
+            ProgressPhase::Downloading => "progress.downloading",
+            ProgressPhase::Paused => "progress.paused",
+            ProgressPhase::Verifying => "progress.verifying",
+            ProgressPhase::Extracting => "progress.extracting",
+            ProgressPhase::BackingUp => "progress.backing_up",
+            ProgressPhase::RemovingOld => "progress.removing_old",
+            ProgressPhase::Installing => "progress.installing",
+            ProgressPhase::Finalizing => "progress.finalizing",
+            ProgressPhase::CleaningUp => "progress.cleaning_up",
+            ProgressPhase::Completed => "progress.completed",
+        };
+
+        let mut line = t!(label);
+        if let Some(file) = &event.current_file {
+            line.push_str(&format!(" {}", file));
+        }
+        if event.bytes_total > 0 {
+            let mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+            line.push_str(&format!(
+                " {:.1}/{:.1} MB ({:.0}%)",
+                mb(event.bytes_done),
+                mb(event.bytes_total),
+                event.bytes_done as f64 * 100.0 / event.bytes_total as f64,
+            ));
+            if event.bytes_per_second > 0 {
+                line.push_str(&format!(" {:.1} MB/s", mb(event.bytes_per_second)));
+            }
+            if let Some(eta) = event.eta_secs {
+                line.push_str(&t!("cli.eta", seconds = eta));
+            }
+        }
+        line
+    }
+
+    fn install_result(&self, result: &InstallResult) {
+        self.print(result, || {
+            let key = if result.delta { "cli.installed_delta" } else { "cli.installed" };
+            let mut lines = vec![t!(key, version = result.version, count = result.files_updated)];
+            lines.extend(result.config_conflicts.iter().map(|conflict| {
+                t!(
+                    "cli.config_kept",
+                    file = conflict.file,
+                    section = conflict.section,
+                    key = conflict.key,
+                    value = conflict.user_value,
+                    default = conflict.new_value,
+                )
+            }));
+            lines.join("\n")
+        });
+    }
+}
+
+// Bản release dùng subsystem "windows" nên cần gắn vào console của terminal đang gọi
+#[cfg(windows)]
+fn attach_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
@@ -1,39 +1,202 @@
-use crate::models::AppConfig;
+use crate::commands::{TranslationState, UpdaterState};
+use crate::err;
+use crate::models::{AppConfig, AppError, DownloadMirror, LaunchMethod, MirrorStat, NetworkConfig, ReleaseSource};
+use crate::services::{
+    create_release_provider, DownloadService, HttpClientService, Locale, LocaleService, LoggingService, TrayService,
+};
+use std::collections::BTreeMap;
 
 #[tauri::command]
-pub async fn load_config() -> Result<AppConfig, String> {
+pub async fn load_config() -> Result<AppConfig, AppError> {
     AppConfig::load()
 }
 
 #[tauri::command]
-pub async fn save_config(config: AppConfig) -> Result<(), String> {
-    config.save()
+pub async fn save_config(app: tauri::AppHandle, config: AppConfig) -> Result<(), AppError> {
+    HttpClientService::reload(&config.network)?;
+    config.save()?;
+
+    let previous = LocaleService::current();
+    if LocaleService::set_language(&config.language) != previous {
+        TrayService::refresh_labels(&app);
+    }
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn update_game_path(path: String) -> Result<(), String> {
+pub async fn update_game_path(path: String) -> Result<(), AppError> {
     let mut config = AppConfig::load()?;
     config.game_path = Some(std::path::PathBuf::from(path));
     config.save()
 }
 
 #[tauri::command]
-pub async fn toggle_auto_update(enabled: bool) -> Result<(), String> {
+pub async fn toggle_auto_update(enabled: bool) -> Result<(), AppError> {
     let mut config = AppConfig::load()?;
     config.auto_update = enabled;
     config.save()
 }
 
 #[tauri::command]
-pub async fn toggle_auto_start(enabled: bool) -> Result<(), String> {
+pub async fn toggle_auto_start(enabled: bool) -> Result<(), AppError> {
     let mut config = AppConfig::load()?;
     config.auto_start = enabled;
     config.save()
 }
 
 #[tauri::command]
-pub async fn set_github_repo(repo: String) -> Result<(), String> {
+pub async fn toggle_close_to_tray(enabled: bool) -> Result<(), AppError> {
+    let mut config = AppConfig::load()?;
+    config.close_to_tray = enabled;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_launch_options(method: LaunchMethod, args: String) -> Result<(), AppError> {
+    let mut config = AppConfig::load()?;
+    config.launch_method = method;
+    config.launch_args = args;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_developer_options(developer_mode: bool, allow_unsigned_packs: bool) -> Result<(), AppError> {
+    let mut config = AppConfig::load()?;
+    config.developer_mode = developer_mode;
+    config.allow_unsigned_packs = developer_mode && allow_unsigned_packs;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_network_config(network: NetworkConfig) -> Result<(), AppError> {
+    let mut config = AppConfig::load()?;
+    config.network = network;
+    // Tạo client trước khi lưu để báo lỗi ngay nếu proxy hoặc chứng chỉ không hợp lệ
+    HttpClientService::reload(&config.network)?;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_update_manifest_url(url: Option<String>) -> Result<(), AppError> {
+    let url = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+    if let Some(url) = &url {
+        if !(url.starts_with("https://") || url.starts_with("http://") || url.starts_with("file://")) {
+            return Err(err!("error.invalid_manifest_url", url = url));
+        }
+    }
+
+    let mut config = AppConfig::load()?;
+    config.update_manifest_url = url;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_download_mirrors(mirrors: Vec<DownloadMirror>) -> Result<(), AppError> {
+    for (index, mirror) in mirrors.iter().enumerate() {
+        mirror.validate()?;
+        if mirrors[..index].iter().any(|other| other.name == mirror.name) {
+            return Err(err!("error.duplicate_mirror", name = mirror.name));
+        }
+    }
+
+    let mut config = AppConfig::load()?;
+    config.download_mirrors = mirrors;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_download_rate_limit(kbps: u64) -> Result<(), AppError> {
+    // Áp dụng ngay cho lượt tải đang chạy
+    DownloadService::set_rate_limit(kbps);
+
+    let mut config = AppConfig::load()?;
+    config.download_rate_limit_kbps = kbps;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_download_connections(connections: u32) -> Result<(), AppError> {
+    if !(1..=16).contains(&connections) {
+        return Err(err!("error.invalid_connections", count = connections));
+    }
+
+    let mut config = AppConfig::load()?;
+    config.download_connections = connections;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn get_mirror_stats() -> Result<BTreeMap<String, MirrorStat>, AppError> {
+    Ok(DownloadService::load_mirror_stats())
+}
+
+#[tauri::command]
+pub async fn set_github_repo(
+    translation_state: tauri::State<'_, TranslationState>,
+    updater_state: tauri::State<'_, UpdaterState>,
+    repo: String,
+) -> Result<(), AppError> {
     let mut config = AppConfig::load()?;
     config.github_repo = repo;
+    config.save()?;
+
+    reload_release_provider(&config, &translation_state, &updater_state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_release_source(
+    translation_state: tauri::State<'_, TranslationState>,
+    updater_state: tauri::State<'_, UpdaterState>,
+    source: ReleaseSource,
+) -> Result<(), AppError> {
+    source.validate()?;
+
+    let mut config = AppConfig::load()?;
+    config.release_source = source;
+    config.save()?;
+
+    reload_release_provider(&config, &translation_state, &updater_state).await;
+    Ok(())
+}
+
+// Áp dụng nguồn release mới mà không cần khởi động lại app
+async fn reload_release_provider(
+    config: &AppConfig,
+    translation_state: &TranslationState,
+    updater_state: &UpdaterState,
+) {
+    translation_state.service.lock().await.set_release_provider(create_release_provider(config));
+    updater_state.service.lock().await.set_release_provider(create_release_provider(config));
+}
+
+#[tauri::command]
+pub async fn set_update_check_interval(hours: u64) -> Result<(), AppError> {
+    let mut config = AppConfig::load()?;
+    config.update_check_interval_hours = hours;
+    config.save()
+}
+
+#[tauri::command]
+pub async fn set_log_level(level: String) -> Result<(), AppError> {
+    LoggingService::set_level(&level)?;
+
+    let mut config = AppConfig::load()?;
+    config.log_level = level;
     config.save()
 }
+
+#[tauri::command]
+pub async fn set_language(app: tauri::AppHandle, language: String) -> Result<(), AppError> {
+    let locale = Locale::from_code(&language)
+        .ok_or_else(|| err!("error.unsupported_language", language = language))?;
+
+    let mut config = AppConfig::load()?;
+    config.language = locale.code().to_string();
+    config.save()?;
+
+    // Thông báo lỗi, hộp thoại và menu khay đổi ngôn ngữ ngay, không cần khởi động lại
+    LocaleService::set_language(locale.code());
+    TrayService::refresh_labels(&app);
+    Ok(())
+}
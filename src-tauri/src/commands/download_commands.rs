@@ -0,0 +1,57 @@
+use crate::commands::{download_and_install_update, install_translation, TranslationState, UpdaterState};
+use crate::err;
+use crate::models::{AppError, QueuedDownload, QueuedOperation};
+use crate::services::{DownloadQueueService, ProgressReporter};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn get_download_queue() -> Result<Vec<QueuedDownload>, AppError> {
+    DownloadQueueService::list()
+}
+
+/// Chạy lại thao tác của lượt tải trong hàng đợi, phần đã tải được giữ lại
+#[tauri::command]
+pub async fn resume_queued_download(
+    app: AppHandle,
+    translation_state: tauri::State<'_, TranslationState>,
+    updater_state: tauri::State<'_, UpdaterState>,
+    id: String,
+) -> Result<(), AppError> {
+    let entry = DownloadQueueService::get(&id)?
+        .ok_or_else(|| err!("error.queue_entry_not_found", id = id))?;
+
+    // Id hàng đợi là tên file tải dở, thao tác tải tiếp dùng id mới để tạm dừng và báo tiến trình
+    match entry.operation {
+        QueuedOperation::Translation { game_path, version } => {
+            let operation_id = ProgressReporter::new_operation_id("install");
+            install_translation(app, translation_state, game_path.to_string_lossy().to_string(), *version, Some(operation_id))
+                .await
+                .map(|_| ())
+        }
+        QueuedOperation::AppUpdate { version } => {
+            // Thông tin tải (URL, chữ ký) lấy lại từ manifest, bản đó phải vẫn là bản mới nhất
+            let update_info = updater_state.service.lock().await
+                .check_for_updates()
+                .await?
+                .filter(|update_info| update_info.version == version);
+
+            match update_info {
+                Some(update_info) => {
+                    let operation_id = ProgressReporter::new_operation_id("app-update");
+                    download_and_install_update(app, updater_state, update_info, Some(operation_id))
+                        .await
+                        .map(|_| ())
+                }
+                None => {
+                    DownloadQueueService::abandon(&id)?;
+                    Err(err!("error.update_no_longer_available", version = version))
+                }
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn abandon_download(id: String) -> Result<(), AppError> {
+    DownloadQueueService::abandon(&id)
+}
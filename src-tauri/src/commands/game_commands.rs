@@ -1,10 +1,12 @@
-use crate::models::GameInfo;
+use crate::err;
+use crate::models::{AppConfig, AppError, GameInfo};
 use crate::services::GameService;
+use crate::t;
 use std::path::PathBuf;
 use tauri_plugin_dialog::MessageDialogKind;
 
 #[tauri::command]
-pub async fn auto_detect_game() -> Result<Option<String>, String> {
+pub async fn auto_detect_game() -> Result<Option<String>, AppError> {
     match GameService::auto_detect_game_path() {
         Some(path) => Ok(Some(path.to_string_lossy().to_string())),
         None => Ok(None),
@@ -12,12 +14,12 @@ pub async fn auto_detect_game() -> Result<Option<String>, String> {
 }
 
 #[tauri::command]
-pub async fn select_game_directory(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn select_game_directory(app: tauri::AppHandle) -> Result<Option<String>, AppError> {
     use tauri_plugin_dialog::DialogExt;
     
     let folder = app.dialog()
         .file()
-        .set_title("Chọn thư mục game Princess Connect Re:Dive")
+        .set_title(t!("dialog.select_game_dir"))
         .blocking_pick_folder();
 
     if let Some(path) = folder {
@@ -36,9 +38,9 @@ pub async fn select_game_directory(app: tauri::AppHandle) -> Result<Option<Strin
             Err(e) => {
                 // Show error dialog
                 app.dialog()
-                    .message(format!("Thư mục không hợp lệ: {}", e))
+                    .message(t!("dialog.invalid_game_dir", error = e))
                     .kind(MessageDialogKind::Error)
-                    .title("Lỗi")
+                    .title(t!("dialog.error_title"))
                     .blocking_show();
                 
                 Err(e)
@@ -50,13 +52,13 @@ pub async fn select_game_directory(app: tauri::AppHandle) -> Result<Option<Strin
 }
 
 #[tauri::command]
-pub async fn validate_game_path(path: String) -> Result<GameInfo, String> {
+pub async fn validate_game_path(path: String) -> Result<GameInfo, AppError> {
     let path_buf = PathBuf::from(path);
     GameService::validate_game_path(path_buf)
 }
 
 #[tauri::command]
-pub async fn get_game_info(path: String) -> Result<GameInfo, String> {
+pub async fn get_game_info(path: String) -> Result<GameInfo, AppError> {
     let path_buf = PathBuf::from(path);
     let mut game_info = GameService::validate_game_path(path_buf)?;
     
@@ -66,3 +68,22 @@ pub async fn get_game_info(path: String) -> Result<GameInfo, String> {
     
     Ok(game_info)
 }
+
+#[tauri::command]
+pub async fn launch_game(game_path: Option<String>) -> Result<(), AppError> {
+    let config = AppConfig::load()?;
+    let game_path = game_path
+        .map(PathBuf::from)
+        .or(config.game_path)
+        .ok_or_else(|| err!("error.game_path_not_set"))?;
+
+    GameService::launch_game(&game_path, config.launch_method, &config.launch_args)
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to launch game"))
+}
+
+#[tauri::command]
+pub async fn is_game_running() -> Result<bool, AppError> {
+    tauri::async_runtime::spawn_blocking(GameService::is_game_running)
+        .await
+        .map_err(|e| err!("error.check_game_process", error = e))
+}
@@ -0,0 +1,20 @@
+use crate::models::{AppError, HistoryEntry};
+use crate::services::HistoryService;
+
+/// Lịch sử thao tác, mới nhất trước
+#[tauri::command]
+pub async fn get_history(limit: Option<usize>) -> Result<Vec<HistoryEntry>, AppError> {
+    let mut entries = HistoryService::load()?;
+    entries.reverse();
+
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn clear_history() -> Result<(), AppError> {
+    HistoryService::clear()
+}
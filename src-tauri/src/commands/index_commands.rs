@@ -0,0 +1,32 @@
+use crate::models::{AppError, SearchField, SearchMode, TranslationSearchResult};
+use crate::services::TranslationIndexService;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// State để giữ chỉ mục bản dịch giữa các lần tìm kiếm
+pub struct IndexState {
+    pub service: Arc<Mutex<TranslationIndexService>>,
+}
+
+#[tauri::command]
+pub async fn build_translation_index(
+    state: tauri::State<'_, IndexState>,
+    game_path: String,
+) -> Result<usize, AppError> {
+    let mut service = state.service.lock().await;
+    service.build(&PathBuf::from(game_path))
+}
+
+#[tauri::command]
+pub async fn search_translations(
+    state: tauri::State<'_, IndexState>,
+    game_path: String,
+    query: String,
+    field: SearchField,
+    mode: SearchMode,
+    limit: Option<usize>,
+) -> Result<Vec<TranslationSearchResult>, AppError> {
+    let mut service = state.service.lock().await;
+    service.search(&PathBuf::from(game_path), &query, field, mode, limit)
+}
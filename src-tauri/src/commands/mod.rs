@@ -3,9 +3,19 @@ pub mod translation_commands;
 pub mod config_commands;
 pub mod system_commands;
 pub mod updater_commands;
+pub mod plugin_commands;
+pub mod override_commands;
+pub mod index_commands;
+pub mod history_commands;
+pub mod download_commands;
 
 pub use game_commands::*;
 pub use translation_commands::*;
 pub use config_commands::*;
 pub use system_commands::*;
 pub use updater_commands::*;
+pub use plugin_commands::*;
+pub use override_commands::*;
+pub use index_commands::*;
+pub use history_commands::*;
+pub use download_commands::*;
@@ -0,0 +1,55 @@
+use crate::models::{AppError, OverrideReport, TranslationOverride};
+use crate::services::{GameService, OverrideService};
+use std::path::PathBuf;
+
+#[tauri::command]
+pub async fn list_overrides() -> Result<Vec<TranslationOverride>, AppError> {
+    OverrideService::load()
+}
+
+#[tauri::command]
+pub async fn add_override(
+    game_path: Option<String>,
+    source: String,
+    translation: String,
+) -> Result<(), AppError> {
+    // Kiểm tra trước khi lưu để không có bản sửa đã lưu mà chưa áp dụng được
+    if game_path.is_some() {
+        GameService::ensure_game_not_running()?;
+    }
+    OverrideService::add_override(&source, &translation)?;
+
+    if let Some(path) = game_path {
+        OverrideService::apply_overrides(&PathBuf::from(path), Some(&[]))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn update_override(
+    game_path: Option<String>,
+    source: String,
+    translation: String,
+) -> Result<(), AppError> {
+    // Kiểm tra trước khi lưu để không có bản sửa đã lưu mà chưa áp dụng được
+    if game_path.is_some() {
+        GameService::ensure_game_not_running()?;
+    }
+    OverrideService::update_override(&source, &translation)?;
+
+    if let Some(path) = game_path {
+        OverrideService::apply_overrides(&PathBuf::from(path), Some(&[]))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_override(game_path: Option<String>, source: String) -> Result<(), AppError> {
+    let path = game_path.map(PathBuf::from);
+    OverrideService::delete_override(path.as_deref(), &source)
+}
+
+#[tauri::command]
+pub async fn apply_overrides(game_path: String) -> Result<OverrideReport, AppError> {
+    OverrideService::apply_overrides(&PathBuf::from(game_path), Some(&[]))
+}
@@ -0,0 +1,25 @@
+use crate::models::{AppError, PluginInfo};
+use crate::services::PluginService;
+use std::path::PathBuf;
+
+#[tauri::command]
+pub async fn list_plugins(game_path: String) -> Result<Vec<PluginInfo>, AppError> {
+    let path = PathBuf::from(game_path);
+    PluginService::list_plugins(&path)
+}
+
+#[tauri::command]
+pub async fn set_plugin_enabled(
+    game_path: String,
+    file_name: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let path = PathBuf::from(game_path);
+    PluginService::set_plugin_enabled(&path, &file_name, enabled)
+}
+
+#[tauri::command]
+pub async fn install_plugin(game_path: String, zip_path: String) -> Result<Vec<String>, AppError> {
+    let path = PathBuf::from(game_path);
+    PluginService::install_plugin_zip(&path, &PathBuf::from(zip_path))
+}
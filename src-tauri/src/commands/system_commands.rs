@@ -1,67 +1,36 @@
-use crate::services::FileService;
+use crate::err;
+use crate::models::{AppError, DownloadPausedEvent, HistoryOperation, LogEntry};
+use crate::services::{DiagnosticsService, DownloadService, FileService, HistoryRecorder, LoggingService};
+use crate::t;
 use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
 
 #[tauri::command]
-pub async fn get_disk_space(path: String) -> Result<(u64, u64), String> {
+pub async fn get_disk_space(path: String) -> Result<(u64, u64), AppError> {
     let path_buf = PathBuf::from(path);
-    
-    #[cfg(target_os = "windows")]
-    {
-        use std::ffi::OsStr;
-        use std::os::windows::ffi::OsStrExt;
-        use winapi::um::fileapi::GetDiskFreeSpaceExW;
-
-        let root = path_buf.ancestors().last()
-            .ok_or_else(|| "Failed to get root path".to_string())?;
-
-        let root_wide: Vec<u16> = OsStr::new(root)
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
-
-        let mut free_bytes: u64 = 0;
-        let mut total_bytes: u64 = 0;
-
-        unsafe {
-            if GetDiskFreeSpaceExW(
-                root_wide.as_ptr(),
-                &mut free_bytes as *mut u64 as *mut _,
-                &mut total_bytes as *mut u64 as *mut _,
-                std::ptr::null_mut(),
-            ) == 0 {
-                return Err("Failed to get disk space".to_string());
-            }
-        }
-
-        Ok((free_bytes, total_bytes))
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        Ok((0, 0))
-    }
+    FileService::get_disk_space(&path_buf)
 }
 
 #[tauri::command]
-pub async fn check_disk_space(path: String, required_bytes: u64) -> Result<bool, String> {
+pub async fn check_disk_space(path: String, required_bytes: u64) -> Result<bool, AppError> {
     let path_buf = PathBuf::from(path);
     FileService::check_disk_space(&path_buf, required_bytes)
 }
 
 #[tauri::command]
-pub async fn get_directory_size(path: String) -> Result<u64, String> {
+pub async fn get_directory_size(path: String) -> Result<u64, AppError> {
     let path_buf = PathBuf::from(path);
     FileService::get_dir_size(&path_buf)
 }
 
 #[tauri::command]
-pub async fn open_directory(path: String) -> Result<(), String> {
+pub async fn open_directory(path: String) -> Result<(), AppError> {
     #[cfg(target_os = "windows")]
     {
         std::process::Command::new("explorer")
             .arg(&path)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| err!("error.open_dir", error = e))?;
     }
 
     #[cfg(target_os = "macos")]
@@ -69,7 +38,7 @@ pub async fn open_directory(path: String) -> Result<(), String> {
         std::process::Command::new("open")
             .arg(&path)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| err!("error.open_dir", error = e))?;
     }
 
     #[cfg(target_os = "linux")]
@@ -77,15 +46,78 @@ pub async fn open_directory(path: String) -> Result<(), String> {
         std::process::Command::new("xdg-open")
             .arg(&path)
             .spawn()
-            .map_err(|e| format!("Failed to open directory: {}", e))?;
+            .map_err(|e| err!("error.open_dir", error = e))?;
     }
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn create_backup(source_path: String, backup_name: String) -> Result<String, String> {
+pub async fn create_backup(source_path: String, backup_name: String) -> Result<String, AppError> {
     let source = PathBuf::from(source_path);
-    let backup_path = FileService::create_backup(&source, &backup_name)?;
-    Ok(backup_path.to_string_lossy().to_string())
+    let history = HistoryRecorder::start(HistoryOperation::Backup, &source, None, None);
+
+    let result = FileService::create_backup(&source, &backup_name)
+        .map(|backup_path| backup_path.to_string_lossy().to_string());
+    history.finish(&result, 0);
+    result
+}
+
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: tauri::AppHandle,
+    game_path: Option<String>,
+) -> Result<Option<String>, AppError> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let file_name = format!(
+        "priconevh_diagnostics_{}.zip",
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    );
+
+    let output = app.dialog()
+        .file()
+        .set_title(t!("dialog.save_diagnostics"))
+        .set_file_name(&file_name)
+        .add_filter("Zip", &["zip"])
+        .blocking_save_file();
+
+    let Some(output) = output else {
+        return Ok(None);
+    };
+
+    let output_path = output.into_path()
+        .map_err(|e| err!("error.invalid_output_path", error = e))?;
+    let game_path = game_path.map(PathBuf::from);
+
+    DiagnosticsService::export(game_path.as_deref(), &output_path)?;
+    Ok(Some(output_path.to_string_lossy().to_string()))
+}
+
+#[tauri::command]
+pub async fn get_app_logs(
+    level: Option<String>,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<LogEntry>, AppError> {
+    LoggingService::read_logs(level.as_deref(), query.as_deref(), limit)
+}
+
+#[tauri::command]
+pub async fn pause_download(app: AppHandle, operation_id: String) -> Result<(), AppError> {
+    DownloadService::pause(&operation_id)?;
+    let _ = app.emit("download-paused-changed", DownloadPausedEvent { operation_id, paused: true });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resume_download(app: AppHandle, operation_id: String) -> Result<(), AppError> {
+    DownloadService::resume(&operation_id);
+    let _ = app.emit("download-paused-changed", DownloadPausedEvent { operation_id, paused: false });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn is_download_paused(operation_id: String) -> Result<bool, AppError> {
+    Ok(DownloadService::is_paused(&operation_id))
 }
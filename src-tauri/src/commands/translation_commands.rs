@@ -1,5 +1,7 @@
-use crate::models::{TranslationPack, TranslationVersion};
-use crate::services::TranslationService;
+use crate::models::{AppError, HistoryOperation, InstallResult, TranslationPack, TranslationVersion};
+use crate::services::translation_service::TranslationInfo;
+use crate::services::{DoorstopService, HistoryRecorder, ProgressReporter, TranslationService, TrayService};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
@@ -9,19 +11,30 @@ pub struct TranslationState {
     pub service: Arc<Mutex<TranslationService>>,
 }
 
+// Tiến trình gửi qua sự kiện `translation-progress`, giao diện có thể tự đặt id để nhận biết thao tác
+pub(crate) fn translation_progress(app: &AppHandle, operation_id: Option<String>, kind: &str) -> ProgressReporter {
+    let app = app.clone();
+    ProgressReporter::new(
+        operation_id.unwrap_or_else(|| ProgressReporter::new_operation_id(kind)),
+        move |event| {
+            let _ = app.emit("translation-progress", event);
+        },
+    )
+}
+
 #[tauri::command]
 pub async fn get_available_translations(
     state: tauri::State<'_, TranslationState>,
-) -> Result<TranslationPack, String> {
+) -> Result<TranslationPack, AppError> {
     let service = state.service.lock().await;
-    service.github_service.get_translation_pack().await
+    service.get_translation_pack().await
 }
 
 #[tauri::command]
 pub async fn check_translation_updates(
     state: tauri::State<'_, TranslationState>,
     current_version: String,
-) -> Result<Option<TranslationVersion>, String> {
+) -> Result<Option<TranslationVersion>, AppError> {
     let service = state.service.lock().await;
     service.check_for_updates(&current_version).await
 }
@@ -32,24 +45,31 @@ pub async fn install_translation(
     state: tauri::State<'_, TranslationState>,
     game_path: String,
     version: TranslationVersion,
-) -> Result<(), String> {
+    operation_id: Option<String>,
+) -> Result<InstallResult, AppError> {
     let service = state.service.lock().await;
-    
-    // Validate game path
-    let game_info = crate::services::GameService::validate_game_path(
-        std::path::PathBuf::from(&game_path)
-    )?;
-
-    // Install với progress callback
-    service.install_translation(
-        &game_info,
-        &version,
-        move |message, progress| {
-            let _ = app.emit("translation-progress", (message, progress));
-        },
-    ).await?;
+    let game_path = PathBuf::from(&game_path);
+    let from_version = TranslationInfo::load(&game_path).map(|info| info.version);
+    let history = HistoryRecorder::start(
+        HistoryOperation::Install,
+        &game_path,
+        from_version,
+        Some(version.version.clone()),
+    );
 
-    Ok(())
+    let progress = translation_progress(&app, operation_id, "install");
+    let result = async {
+        // Validate game path
+        let game_info = crate::services::GameService::validate_game_path(game_path.clone())?;
+
+        service.install_translation(&game_info, &version, &progress).await
+    }
+    .await
+    .inspect_err(|e| tracing::error!(error = %e, "Install failed"));
+
+    history.finish(&result, result.as_ref().map(|r| r.bytes_downloaded).unwrap_or(0));
+    TrayService::refresh_status(&app);
+    result
 }
 
 #[tauri::command]
@@ -58,39 +78,89 @@ pub async fn update_translation(
     state: tauri::State<'_, TranslationState>,
     game_path: String,
     new_version: TranslationVersion,
-) -> Result<(), String> {
+    operation_id: Option<String>,
+) -> Result<InstallResult, AppError> {
     let service = state.service.lock().await;
-    
-    let game_info = crate::services::GameService::validate_game_path(
-        std::path::PathBuf::from(&game_path)
-    )?;
-
-    service.update_translation(
-        &game_info,
-        &new_version,
-        move |message, progress| {
-            let _ = app.emit("translation-progress", (message, progress));
-        },
-    ).await?;
+    let game_path = PathBuf::from(&game_path);
+    let from_version = TranslationInfo::load(&game_path).map(|info| info.version);
+    let history = HistoryRecorder::start(
+        HistoryOperation::Update,
+        &game_path,
+        from_version,
+        Some(new_version.version.clone()),
+    );
 
-    Ok(())
+    let progress = translation_progress(&app, operation_id, "update");
+    let result = async {
+        let game_info = crate::services::GameService::validate_game_path(game_path.clone())?;
+
+        service.update_translation(&game_info, &new_version, &progress).await
+    }
+    .await
+    .inspect_err(|e| tracing::error!(error = %e, "Update failed"));
+
+    history.finish(&result, result.as_ref().map(|r| r.bytes_downloaded).unwrap_or(0));
+    TrayService::refresh_status(&app);
+    result
 }
 
 #[tauri::command]
 pub async fn uninstall_translation(
+    app: AppHandle,
     state: tauri::State<'_, TranslationState>,
     game_path: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let service = state.service.lock().await;
-    let path = std::path::PathBuf::from(game_path);
-    service.uninstall_translation(&path)
+    let path = PathBuf::from(game_path);
+    let from_version = TranslationInfo::load(&path).map(|info| info.version);
+    let history = HistoryRecorder::start(HistoryOperation::Uninstall, &path, from_version, None);
+
+    let result = service.uninstall_translation(&path);
+    history.finish(&result, 0);
+    TrayService::refresh_status(&app);
+    result
+}
+
+#[tauri::command]
+pub async fn restore_translation_backup(
+    app: AppHandle,
+    state: tauri::State<'_, TranslationState>,
+    game_path: String,
+) -> Result<Option<String>, AppError> {
+    let service = state.service.lock().await;
+    let path = PathBuf::from(game_path);
+    let from_version = TranslationInfo::load(&path).map(|info| info.version);
+    let backup_version = TranslationInfo::load(&path.join("translation_backup")).map(|info| info.version);
+    let history = HistoryRecorder::start(HistoryOperation::Restore, &path, from_version, backup_version);
+
+    let result = service.restore_translation_backup(&path);
+    history.finish(&result, 0);
+    TrayService::refresh_status(&app);
+    result
+}
+
+#[tauri::command]
+pub async fn set_translation_enabled(
+    app: AppHandle,
+    state: tauri::State<'_, TranslationState>,
+    game_path: String,
+    enabled: bool,
+) -> Result<(), AppError> {
+    // Giữ lock để không đổi trạng thái khi đang cài/cập nhật
+    let _service = state.service.lock().await;
+    let path = PathBuf::from(game_path);
+
+    let result = DoorstopService::set_enabled(&path, enabled)
+        .inspect_err(|e| tracing::error!(error = %e, "Failed to toggle translation"));
+    TrayService::refresh_status(&app);
+    result
 }
 
 #[tauri::command]
 pub async fn get_translation_info(
     state: tauri::State<'_, TranslationState>,
     game_path: String,
-) -> Result<Option<crate::services::translation_service::TranslationInfo>, String> {
+) -> Result<Option<crate::services::translation_service::TranslationInfo>, AppError> {
     let service = state.service.lock().await;
     let path = std::path::PathBuf::from(game_path);
     Ok(service.get_current_translation_info(&path))
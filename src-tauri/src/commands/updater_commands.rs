@@ -1,4 +1,6 @@
-use crate::services::{UpdaterService, AppUpdateInfo};
+use crate::err;
+use crate::models::{AppError, PendingUpdate};
+use crate::services::{ProgressReporter, UpdaterService, AppUpdateInfo};
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
@@ -11,7 +13,7 @@ pub struct UpdaterState {
 #[tauri::command]
 pub async fn check_app_update(
     state: tauri::State<'_, UpdaterState>,
-) -> Result<Option<AppUpdateInfo>, String> {
+) -> Result<Option<AppUpdateInfo>, AppError> {
     let service = state.service.lock().await;
     service.check_for_updates().await
 }
@@ -21,36 +23,29 @@ pub async fn download_and_install_update(
     app: AppHandle,
     state: tauri::State<'_, UpdaterState>,
     update_info: AppUpdateInfo,
-) -> Result<(), String> {
+    operation_id: Option<String>,
+) -> Result<PendingUpdate, AppError> {
     let service = state.service.lock().await;
-    
-    // Download installer
-    let installer_path = service.download_and_install(
-        &update_info,
-        move |message, progress| {
-            let _ = app.emit("updater-progress", (message, progress));
+    let progress = ProgressReporter::new(
+        operation_id.unwrap_or_else(|| ProgressReporter::new_operation_id("app-update")),
+        move |event| {
+            let _ = app.emit("updater-progress", event);
         },
-    ).await?;
+    );
 
-    // Chạy MSI installer
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        
-        let installer_str = installer_path.to_string_lossy().to_string();
-        
-        // Chạy MSI installer với quyền admin
-        Command::new("msiexec")
-            .args(&["/i", &installer_str])
-            .spawn()
-            .map_err(|e| format!("Failed to run installer: {}", e))?;
-        
-        // Đóng app hiện tại để installer có thể cập nhật
-        std::process::exit(0);
-    }
+    // Tải và kiểm tra chữ ký, bản cập nhật được cài khi khởi động lại app
+    service.download_and_stage(&update_info, &progress).await
+}
+
+#[tauri::command]
+pub async fn get_pending_update() -> Result<Option<PendingUpdate>, AppError> {
+    Ok(UpdaterService::load_pending_update())
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        Err("Auto update only supported on Windows".to_string())
+#[tauri::command]
+pub async fn restart_to_update(app: AppHandle) -> Result<(), AppError> {
+    if UpdaterService::load_pending_update().is_none() {
+        return Err(err!("error.no_update_ready"));
     }
+    app.restart()
 }
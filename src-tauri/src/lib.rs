@@ -1,40 +1,96 @@
 mod models;
 mod services;
 mod commands;
+pub mod cli;
 
 use commands::*;
 use models::AppConfig;
-use services::{TranslationService, UpdaterService};
+use services::{
+    create_release_provider, DownloadQueueService, GameMonitorService, LocaleService, LoggingService, SchedulerService, TranslationIndexService, TranslationService,
+    TrayService, UpdaterService,
+};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Ngôn ngữ và log cần khởi tạo trước tất cả để ghi lại cả lỗi lúc khởi động
+    LocaleService::init();
+    let log_level = AppConfig::load()
+        .map(|config| config.log_level)
+        .unwrap_or_else(|_| "info".to_string());
+    if let Err(e) = LoggingService::init(&log_level) {
+        LoggingService::init_stderr(&log_level);
+        tracing::warn!(error = %e, "File logging unavailable, logging to stderr");
+    }
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "Application starting");
+
     tauri::Builder::default()
         // Setup state
         .setup(|app| {
+            // Cài bản cập nhật app đã tải ở lần chạy trước
+            let app_version = app.package_info().version.to_string();
+            match UpdaterService::apply_pending_update(&app_version) {
+                Ok(true) => std::process::exit(0),
+                Ok(false) => {}
+                Err(e) => tracing::error!(error = %e, "Failed to apply staged update"),
+            }
+            
+            // Lượt tải dở của lần chạy trước, giao diện sẽ hỏi tải tiếp hay bỏ
+            if let Err(e) = DownloadQueueService::recover() {
+                tracing::warn!(error = %e, "Failed to recover download queue");
+            }
+            
             // Load config
             let config = AppConfig::load().unwrap_or_default();
             
             // Initialize TranslationService
-            let translation_service = TranslationService::new(config.github_repo.clone());
+            let translation_service = TranslationService::new(create_release_provider(&config));
             let translation_state = TranslationState {
                 service: Arc::new(Mutex::new(translation_service)),
             };
             
             // Initialize UpdaterService
-            let app_version = app.package_info().version.to_string();
             let updater_service = UpdaterService::new(
-                config.github_repo.clone(),
+                create_release_provider(&config),
                 app_version,
             );
             let updater_state = UpdaterState {
                 service: Arc::new(Mutex::new(updater_service)),
             };
             
+            // Initialize TranslationIndexService
+            let index_state = IndexState {
+                service: Arc::new(Mutex::new(TranslationIndexService::new())),
+            };
+            
+            let translation_service = translation_state.service.clone();
+            let updater_service = updater_state.service.clone();
+            
+            // Scheduler tự cập nhật qua lệnh `update_translation` nên state phải có trước khi chạy
             use tauri::Manager;
             app.manage(translation_state);
             app.manage(updater_state);
+            app.manage(index_state);
+            
+            // Kiểm tra cập nhật chạy nền (lúc khởi động và theo chu kỳ)
+            SchedulerService::new(
+                app.handle().clone(),
+                translation_service.clone(),
+                updater_service.clone(),
+            ).start();
+            
+            // Icon khay hệ thống
+            TrayService::setup(app.handle(), translation_service, updater_service)?;
+            
+            // Theo dõi game đang chạy để UI khóa các thao tác cài đặt
+            GameMonitorService::start(app.handle().clone());
+            
+            // Khởi động cùng hệ thống thì chỉ chạy dưới khay
+            let start_minimized = std::env::args().any(|arg| arg == "--minimized");
+            if !start_minimized {
+                TrayService::show_main_window(app.handle());
+            }
             
             // Setup autostart if enabled
             if config.auto_start {
@@ -58,8 +114,21 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            None,
+            Some(vec!["--minimized"]),
         ))
+        // Đóng cửa sổ thì ẩn xuống khay nếu bật trong cài đặt
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                let close_to_tray = AppConfig::load()
+                    .map(|config| config.close_to_tray)
+                    .unwrap_or(false);
+                
+                if close_to_tray {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         // Commands
         .invoke_handler(tauri::generate_handler![
             // Game commands
@@ -67,6 +136,8 @@ pub fn run() {
             select_game_directory,
             validate_game_path,
             get_game_info,
+            launch_game,
+            is_game_running,
             
             // Translation commands
             get_available_translations,
@@ -74,6 +145,8 @@ pub fn run() {
             install_translation,
             update_translation,
             uninstall_translation,
+            restore_translation_backup,
+            set_translation_enabled,
             get_translation_info,
             
             // Config commands
@@ -82,18 +155,63 @@ pub fn run() {
             update_game_path,
             toggle_auto_update,
             toggle_auto_start,
+            toggle_close_to_tray,
+            set_launch_options,
+            set_developer_options,
+            set_update_manifest_url,
+            set_network_config,
+            set_download_mirrors,
+            set_download_rate_limit,
+            set_download_connections,
+            get_mirror_stats,
             set_github_repo,
+            set_release_source,
+            set_log_level,
+            set_language,
+            set_update_check_interval,
             
             // System commands
             get_disk_space,
             check_disk_space,
+            export_diagnostics,
+            get_app_logs,
             get_directory_size,
             open_directory,
             create_backup,
+            pause_download,
+            resume_download,
+            is_download_paused,
             
             // Updater commands
             check_app_update,
             download_and_install_update,
+            get_pending_update,
+            restart_to_update,
+            
+            // Plugin commands
+            list_plugins,
+            set_plugin_enabled,
+            install_plugin,
+            
+            // Override commands
+            list_overrides,
+            add_override,
+            update_override,
+            delete_override,
+            apply_overrides,
+            
+            // Index commands
+            build_translation_index,
+            search_translations,
+            
+            // History commands
+            get_history,
+            clear_history,
+            
+            // Download queue commands
+            get_download_queue,
+            resume_queued_download,
+            abandon_download,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -2,5 +2,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Có subcommand thì chạy dạng dòng lệnh, không mở cửa sổ
+    if let Some(code) = priconevh_lib::cli::run_from_args() {
+        std::process::exit(code);
+    }
+
     priconevh_lib::run()
 }
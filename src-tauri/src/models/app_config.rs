@@ -1,14 +1,157 @@
+use crate::err;
+use crate::models::{AppError, DownloadMirror};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchMethod {
+    #[default]
+    Dmm,     // Qua DMM Game Player (cần đăng nhập DMM)
+    Direct,  // Chạy trực tiếp file exe
+}
+
+/// Nơi lấy danh sách release (bản việt hóa và bản cập nhật app)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ReleaseSource {
+    #[default]
+    GitHub,
+    Gitea { base_url: String },  // Gitea/Forgejo tự host, repo lấy từ github_repo
+    Manifest { url: String },    // File JSON danh sách release, mới nhất trước
+    Local { path: PathBuf },     // Mỗi release là một thư mục con đặt tên theo tag
+}
+
+/// Giới hạn khi giải nén để file nén lỗi hoặc độc hại (zip bomb) không làm đầy ổ đĩa
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExtractLimits {
+    pub max_total_size: u64,  // Tổng dung lượng sau khi giải nén (byte)
+    pub max_entries: usize,
+    pub max_ratio: u64,       // Tỉ lệ nén tối đa của mỗi file (chỉ xét file lớn hơn 1MB)
+}
+
+impl Default for ExtractLimits {
+    fn default() -> Self {
+        Self {
+            max_total_size: 4 * 1024 * 1024 * 1024,
+            max_entries: 50_000,
+            max_ratio: 200,
+        }
+    }
+}
+
+/// Proxy dùng cho mọi kết nối mạng
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum ProxyConfig {
+    Direct,  // Không dùng proxy
+    #[default]
+    System,  // Biến môi trường HTTP(S)_PROXY/ALL_PROXY và proxy của hệ điều hành
+    Custom {
+        url: String,  // http://, https://, socks5:// hoặc socks5h://
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        #[serde(default)]
+        no_proxy: String,  // Các host không đi qua proxy, cách nhau bởi dấu phẩy
+    },
+}
+
+/// Cài đặt mạng cho HTTP client dùng chung
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub proxy: ProxyConfig,
+    pub extra_ca_certs: Vec<PathBuf>,  // File chứng chỉ CA (PEM hoặc DER) tin cậy thêm
+    pub connect_timeout_secs: u64,
+    pub read_timeout_secs: u64,  // 0 = không giới hạn
+    pub user_agent: String,      // Rỗng = mặc định
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: ProxyConfig::default(),
+            extra_ca_certs: Vec::new(),
+            connect_timeout_secs: 15,
+            read_timeout_secs: 60,
+            user_agent: String::new(),
+        }
+    }
+}
+
+impl ReleaseSource {
+    pub fn validate(&self) -> Result<(), AppError> {
+        match self {
+            ReleaseSource::GitHub => Ok(()),
+            ReleaseSource::Gitea { base_url: url } | ReleaseSource::Manifest { url } => {
+                if url.starts_with("https://") || url.starts_with("http://") || url.starts_with("file://") {
+                    Ok(())
+                } else {
+                    Err(err!("error.invalid_release_source_url", url = url))
+                }
+            }
+            ReleaseSource::Local { path } => {
+                if path.is_dir() {
+                    Ok(())
+                } else {
+                    Err(err!("error.release_dir_not_found", path = path.display()))
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub game_path: Option<PathBuf>,
     pub auto_update: bool,
     pub auto_start: bool,
     pub github_repo: String,
+    #[serde(default)]
+    pub release_source: ReleaseSource,
     pub check_update_on_startup: bool,
     pub language: String,
+    #[serde(default = "default_log_level")]
+    pub log_level: String,  // trace, debug, info, warn, error
+    #[serde(default = "default_update_check_interval")]
+    pub update_check_interval_hours: u64,  // 0 = tắt kiểm tra định kỳ
+    #[serde(default)]
+    pub close_to_tray: bool,  // Đóng cửa sổ thì ẩn xuống khay thay vì thoát, mặc định tắt
+    #[serde(default)]
+    pub launch_method: LaunchMethod,
+    #[serde(default)]
+    pub launch_args: String,  // Tham số khi chạy trực tiếp file exe
+    #[serde(default)]
+    pub download_mirrors: Vec<DownloadMirror>,  // Thử theo thứ tự trước khi tải từ GitHub
+    #[serde(default)]
+    pub download_rate_limit_kbps: u64,  // 0 = không giới hạn
+    #[serde(default = "default_download_connections")]
+    pub download_connections: u32,  // Số kết nối song song khi tải file lớn, 1 = một luồng
+    #[serde(default)]
+    pub extract_limits: ExtractLimits,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub update_manifest_url: Option<String>,  // None = asset latest.json của release mới nhất
+    #[serde(default)]
+    pub developer_mode: bool,
+    #[serde(default)]
+    pub allow_unsigned_packs: bool,  // Chỉ có tác dụng khi bật developer_mode
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_update_check_interval() -> u64 {
+    6
+}
+
+fn default_download_connections() -> u32 {
+    4
 }
 
 impl Default for AppConfig {
@@ -18,48 +161,72 @@ impl Default for AppConfig {
             auto_update: true,
             auto_start: false,
             github_repo: "TomiWixoss/priconevh".to_string(),
+            release_source: ReleaseSource::default(),
             check_update_on_startup: true,
             language: "vi".to_string(),
+            log_level: default_log_level(),
+            update_check_interval_hours: default_update_check_interval(),
+            close_to_tray: false,
+            launch_method: LaunchMethod::default(),
+            launch_args: String::new(),
+            download_mirrors: Vec::new(),
+            download_rate_limit_kbps: 0,
+            download_connections: default_download_connections(),
+            extract_limits: ExtractLimits::default(),
+            network: NetworkConfig::default(),
+            update_manifest_url: None,
+            developer_mode: false,
+            allow_unsigned_packs: false,
         }
     }
 }
 
 impl AppConfig {
-    pub fn load() -> Result<Self, String> {
+    pub fn load() -> Result<Self, AppError> {
         let config_path = Self::get_config_path()?;
         
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path)
-                .map_err(|e| format!("Failed to read config: {}", e))?;
+                .map_err(|e| err!("error.read_config", error = e))?;
             
             serde_json::from_str(&content)
-                .map_err(|e| format!("Failed to parse config: {}", e))
+                .map_err(|e| err!("error.parse_config", error = e))
         } else {
             Ok(Self::default())
         }
     }
 
-    pub fn save(&self) -> Result<(), String> {
+    pub fn save(&self) -> Result<(), AppError> {
         let config_path = Self::get_config_path()?;
         
         if let Some(parent) = config_path.parent() {
             std::fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+                .map_err(|e| err!("error.create_config_dir", error = e))?;
         }
 
         let content = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+            .map_err(|e| err!("error.serialize_config", error = e))?;
 
         std::fs::write(&config_path, content)
-            .map_err(|e| format!("Failed to write config: {}", e))?;
+            .map_err(|e| err!("error.write_config", error = e))?;
 
         Ok(())
     }
 
-    fn get_config_path() -> Result<PathBuf, String> {
+    fn get_config_path() -> Result<PathBuf, AppError> {
+        Ok(Self::get_data_dir()?.join("config.json"))
+    }
+
+    /// Thư mục dữ liệu của app (config, dữ liệu người dùng, ...)
+    pub fn get_data_dir() -> Result<PathBuf, AppError> {
         let config_dir = dirs::config_dir()
-            .ok_or_else(|| "Failed to get config directory".to_string())?;
+            .ok_or_else(|| err!("error.config_dir"))?;
         
-        Ok(config_dir.join("priconevh").join("config.json"))
+        Ok(config_dir.join("priconevh"))
+    }
+
+    /// Thư mục log của app
+    pub fn get_logs_dir() -> Result<PathBuf, AppError> {
+        Ok(Self::get_data_dir()?.join("logs"))
     }
 }
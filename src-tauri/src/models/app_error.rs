@@ -0,0 +1,125 @@
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// Lỗi trả về cho giao diện, kèm mã phân loại (`HistoryEntry.error_code`) lấy từ key trong danh mục
+///
+/// Tạo bằng `err!("error.key", ...)`. Giao diện chỉ nhận nội dung thông báo, mã không phụ thuộc
+/// ngôn ngữ đang dùng.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppError {
+    code: &'static str,
+    message: String,
+}
+
+impl AppError {
+    pub const UNKNOWN: &'static str = "unknown";
+
+    /// `key` là key của thông báo trong danh mục, mã lỗi hệ điều hành trong thông báo được xét trước
+    pub fn new(key: &str, message: String) -> Self {
+        let code = Self::os_error_code(&message)
+            .or_else(|| Self::catalogue_code(key.trim_start_matches("error.")))
+            .unwrap_or(Self::UNKNOWN);
+
+        Self { code, message }
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Giữ mã của lỗi bên trong nếu lỗi đó đã được phân loại, vd: lỗi tải từ mọi nguồn vì sai hash
+    /// là lỗi toàn vẹn chứ không phải lỗi mạng
+    pub fn caused_by(mut self, inner: &AppError) -> Self {
+        if inner.code != Self::UNKNOWN {
+            self.code = inner.code;
+        }
+        self
+    }
+
+    /// Mã lỗi hệ điều hành mà std::io::Error luôn thêm vào cuối dạng `(os error N)`
+    fn os_error_code(message: &str) -> Option<&'static str> {
+        #[cfg(windows)]
+        const DISK_FULL: &[i32] = &[39, 112];  // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+        #[cfg(windows)]
+        const PERMISSION_DENIED: &[i32] = &[5, 32];  // ERROR_ACCESS_DENIED, ERROR_SHARING_VIOLATION
+        #[cfg(not(windows))]
+        const DISK_FULL: &[i32] = &[28];  // ENOSPC
+        #[cfg(not(windows))]
+        const PERMISSION_DENIED: &[i32] = &[1, 13];  // EPERM, EACCES
+
+        message.match_indices("(os error ")
+            .filter_map(|(index, marker)| {
+                let rest = &message[index + marker.len()..];
+                rest[..rest.find(')')?].parse::<i32>().ok()
+            })
+            .find_map(|code| {
+                if DISK_FULL.contains(&code) {
+                    Some("disk_full")
+                } else if PERMISSION_DENIED.contains(&code) {
+                    Some("permission_denied")
+                } else {
+                    None
+                }
+            })
+    }
+
+    fn catalogue_code(key: &str) -> Option<&'static str> {
+        let code = match key {
+            "disk_space" | "extract_disk_space" => "disk_full",
+            "invalid_signature_file" | "invalid_manifest_signature" | "signature_failed" | "signature_mismatch"
+            | "untrusted_key" | "no_trusted_keys" | "pack_not_signed" | "manifest_not_signed"
+            | "update_manifest_not_signed" => "signature",
+            "hash_mismatch" | "size_mismatch" | "range_length" | "range_overflow" | "invalid_range" => "integrity",
+            "download_all_sources" | "download_status" | "download_timeout" | "downloading" | "downloading_range"
+            | "fetch_latest_release" | "fetch_range" | "fetch_releases" | "fetch_url" | "github_api"
+            | "connection_closed_at" | "start_download" | "range_unsupported" | "resume_unsupported" => "network",
+            "archive_entry_ratio" | "archive_entry_size" | "archive_file_not_found" | "archive_not_found"
+            | "archive_ratio" | "archive_size_unknown" | "archive_symlink" | "archive_too_large"
+            | "archive_too_many_entries" | "archive_unsafe_path" | "eocd_not_found" | "extract_7z" | "extract_file"
+            | "invalid_central_directory" | "invalid_central_directory_entry" | "invalid_local_header"
+            | "open_archive" | "open_zip" | "read_7z" | "read_archive" | "read_archive_file" | "read_tar"
+            | "read_zip" | "read_zstd" | "truncated_data" | "unsupported_archive" | "unsupported_compression"
+            | "zip64_unsupported" => "archive",
+            "config_dir" | "create_config_dir" | "read_config" | "parse_config" | "serialize_config"
+            | "write_config" => "config",
+            "game_running" | "game_running_close" => "game_running",
+            "game_dir_invalid" | "game_executable_not_found" | "game_files_missing" | "game_path_not_set" => {
+                "invalid_game_path"
+            }
+            _ => return None,
+        };
+        Some(code)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+// Thông báo không tạo từ danh mục (vd: lỗi của thư viện) chỉ xét mã lỗi hệ điều hành, không đoán theo nội dung
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new("", message)
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message
+    }
+}
+
+// Giao diện nhận lỗi dạng chuỗi như trước
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.message)
+    }
+}
@@ -0,0 +1,41 @@
+/// Định dạng file nén bản việt hóa/plugin được hỗ trợ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    SevenZip,
+    TarZst,
+}
+
+impl ArchiveFormat {
+    /// Đoán định dạng từ tên file (dùng khi chọn asset của release)
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".7z") {
+            Some(ArchiveFormat::SevenZip)
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(ArchiveFormat::TarZst)
+        } else {
+            None
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::SevenZip => "7z",
+            ArchiveFormat::TarZst => "tar.zst",
+        }
+    }
+}
+
+/// Một mục trong file nén
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub size: u64,
+    pub compressed_size: Option<u64>,  // None nếu định dạng không lưu riêng từng file (7z solid, tar.zst)
+    pub is_dir: bool,
+    pub is_link: bool,  // Symlink, hardlink hoặc loại file đặc biệt
+}
@@ -0,0 +1,83 @@
+use crate::err;
+use crate::models::AppError;
+use serde::{Deserialize, Serialize};
+
+/// Nguồn tải thay thế cho asset trên GitHub Releases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadMirror {
+    pub name: String,
+    pub url_template: String,  // Dùng {repo}, {tag}, {asset}, vd: https://mirror.example/{repo}/{tag}/{asset}
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,  // Hủy nếu không nhận được dữ liệu sau khoảng thời gian này
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+impl DownloadMirror {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(err!("error.mirror_name_empty"));
+        }
+        if !self.url_template.starts_with("https://") && !self.url_template.starts_with("http://") {
+            return Err(err!("error.mirror_url_scheme", name = self.name));
+        }
+        if !self.url_template.contains("{asset}") {
+            return Err(err!("error.mirror_url_placeholder", name = self.name));
+        }
+
+        Ok(())
+    }
+
+    pub fn resolve_url(&self, asset: &ReleaseAsset) -> String {
+        self.url_template
+            .replace("{repo}", &asset.repo)
+            .replace("{tag}", &asset.tag)
+            .replace("{asset}", &asset.name)
+    }
+}
+
+/// Tốc độ tải lần gần nhất của mỗi nguồn, dùng để ưu tiên nguồn nhanh nhất
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorStat {
+    pub bytes_per_second: u64,  // 0 nếu lần gần nhất bị lỗi
+    pub updated_at: String,
+}
+
+/// Asset của release kèm thông tin gốc trên GitHub để kiểm tra file tải từ mirror
+#[derive(Debug, Clone)]
+pub struct ReleaseAsset {
+    pub repo: String,
+    pub tag: String,
+    pub name: String,
+    pub url: String,  // URL gốc trên GitHub
+    pub size: u64,
+    pub sha256: Option<String>,
+}
+
+impl ReleaseAsset {
+    pub fn new(repo: &str, tag: &str, name: &str, url: &str, size: u64, sha256: Option<String>) -> Self {
+        // Dữ liệu cũ từ frontend có thể thiếu tên asset, lấy từ URL
+        let name = if name.is_empty() {
+            url.rsplit('/').next().unwrap_or_default()
+        } else {
+            name
+        };
+
+        Self {
+            repo: repo.to_string(),
+            tag: tag.to_string(),
+            name: name.to_string(),
+            url: url.to_string(),
+            size,
+            sha256,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::models::TranslationVersion;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Thao tác cần chạy lại để tải tiếp một mục trong hàng đợi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QueuedOperation {
+    Translation {
+        game_path: PathBuf,
+        version: Box<TranslationVersion>,
+    },
+    AppUpdate {
+        version: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueStatus {
+    Downloading,
+    Interrupted,  // Lỗi hoặc app bị tắt giữa chừng, có thể tải tiếp
+}
+
+/// Lượt tải chưa xong, được lưu lại để tải tiếp sau khi khởi động lại app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedDownload {
+    pub id: String,
+    pub operation: QueuedOperation,
+    pub asset_name: String,
+    pub file_path: PathBuf,  // File đang tải dở
+    pub status: QueueStatus,
+    #[serde(default)]
+    pub downloaded_bytes: u64,
+    #[serde(default)]
+    pub total_bytes: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Trạng thái tải dở của một file, lưu cạnh file dưới dạng `<file>.part.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartialDownload {
+    #[serde(default)]
+    pub url: String,            // Nguồn đã ghi phần tải dở, nguồn khác thì phải tải lại từ đầu
+    #[serde(default)]
+    pub etag: Option<String>,
+    pub total_size: u64,
+    pub segments: Vec<DownloadSegment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadSegment {
+    pub start: u64,
+    pub end: u64,     // Byte cuối của đoạn (tính cả byte này)
+    pub offset: u64,  // Byte tiếp theo cần tải, lớn hơn `end` là đã xong
+}
+
+impl PartialDownload {
+    /// Chia file thành `count` đoạn gần bằng nhau
+    pub fn split(url: &str, etag: Option<String>, total_size: u64, count: u32) -> Self {
+        let segment_size = total_size.div_ceil(count.max(1) as u64).max(1);
+        let segments = (0..count.max(1) as u64)
+            .map(|index| index * segment_size)
+            .take_while(|start| *start < total_size)
+            .map(|start| DownloadSegment {
+                start,
+                end: (start + segment_size).min(total_size) - 1,
+                offset: start,
+            })
+            .collect();
+
+        Self { url: url.to_string(), etag, total_size, segments }
+    }
+
+    /// Phần tải dở chỉ dùng tiếp được khi cùng nguồn và file trên server chưa đổi
+    pub fn is_from(&self, url: &str, etag: Option<&str>, total_size: u64) -> bool {
+        self.url == url && self.etag.as_deref() == etag && self.total_size == total_size
+    }
+
+    pub fn downloaded(&self) -> u64 {
+        self.segments.iter()
+            .map(|segment| segment.offset.min(segment.end + 1) - segment.start)
+            .sum()
+    }
+}
@@ -8,6 +8,7 @@ pub struct GameInfo {
     pub is_valid: bool,
     pub has_translation: bool,
     pub translation_version: Option<String>,
+    pub translation_enabled: bool,  // false khi Doorstop bị tắt (chạy bản gốc)
 }
 
 impl GameInfo {
@@ -18,12 +19,13 @@ impl GameInfo {
             is_valid: false,
             has_translation: false,
             translation_version: None,
+            translation_enabled: false,
         }
     }
 
     pub fn validate(&mut self) -> bool {
         // Kiểm tra các file quan trọng của game Princess Connect Re:Dive
-        let required_files = vec![
+        let required_files = [
             "PrincessConnectReDive.exe",
             "UnityPlayer.dll",
             "GameAssembly.dll",
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOperation {
+    Install,
+    Update,
+    Uninstall,
+    Backup,
+    Restore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryOutcome {
+    Success,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub operation: HistoryOperation,
+    pub profile: String,  // Thư mục game thực hiện thao tác
+    pub from_version: Option<String>,
+    pub to_version: Option<String>,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub bytes_downloaded: u64,
+    pub outcome: HistoryOutcome,
+    pub error_code: Option<String>,
+    pub error_message: Option<String>,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigConflict {
+    pub file: String,
+    pub section: String,
+    pub key: String,
+    pub base_value: Option<String>,  // Giá trị mặc định của bản việt hóa cũ
+    pub user_value: String,          // Giá trị người dùng đang dùng (được giữ lại)
+    pub new_value: String,           // Giá trị mặc định của bản việt hóa mới
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallResult {
+    pub version: String,
+    pub config_conflicts: Vec<ConfigConflict>,
+    pub delta: bool,          // Cập nhật bằng delta thay vì tải toàn bộ
+    pub files_updated: usize,
+    pub bytes_downloaded: u64,
+    pub obsolete_overrides: Vec<String>,  // Bản sửa của người dùng mà bản mới đã dịch giống
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    pub fields: serde_json::Map<String, serde_json::Value>,  // Các field khác ngoài message
+    pub spans: Vec<String>,  // Tên các span bao ngoài (vd: install_translation > download)
+}
@@ -1,7 +1,33 @@
 pub mod game_info;
 pub mod translation_pack;
 pub mod app_config;
+pub mod plugin_info;
+pub mod install_result;
+pub mod translation_manifest;
+pub mod translation_override;
+pub mod translation_search;
+pub mod log_entry;
+pub mod history_entry;
+pub mod download_mirror;
+pub mod archive;
+pub mod update_manifest;
+pub mod download_queue;
+pub mod progress;
+pub mod app_error;
 
 pub use game_info::GameInfo;
-pub use translation_pack::{TranslationPack, TranslationVersion};
-pub use app_config::AppConfig;
+pub use translation_pack::{Release, ReleaseFile, TranslationPack, TranslationVersion, SIGNATURE_EXTENSION};
+pub use app_config::{AppConfig, ExtractLimits, LaunchMethod, NetworkConfig, ProxyConfig, ReleaseSource};
+pub use plugin_info::PluginInfo;
+pub use install_result::{ConfigConflict, InstallResult};
+pub use translation_manifest::{ManifestFile, TranslationManifest, VerifyReport};
+pub use translation_override::{OverrideReport, TranslationOverride};
+pub use translation_search::{SearchField, SearchMode, TranslationSearchResult};
+pub use log_entry::LogEntry;
+pub use history_entry::{HistoryEntry, HistoryOperation, HistoryOutcome};
+pub use download_mirror::{DownloadMirror, MirrorStat, ReleaseAsset};
+pub use archive::{ArchiveEntry, ArchiveFormat};
+pub use update_manifest::{InstallerKind, PendingUpdate, UpdateManifest, UpdatePlatform};
+pub use download_queue::{DownloadSegment, PartialDownload, QueueStatus, QueuedDownload, QueuedOperation};
+pub use progress::{DownloadPausedEvent, ProgressEvent, ProgressPhase};
+pub use app_error::AppError;
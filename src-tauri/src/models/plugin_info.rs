@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInfo {
+    pub file_name: String,  // Đường dẫn tương đối trong BepInEx/plugins (không có đuôi .disabled)
+    pub name: String,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub company: Option<String>,
+    pub size: u64,
+    pub enabled: bool,
+    pub from_translation_pack: bool,
+}
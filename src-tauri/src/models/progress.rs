@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+/// Giai đoạn của thao tác cài đặt/cập nhật, nội dung hiển thị do giao diện dịch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressPhase {
+    Preparing,
+    CheckingChanges,
+    Downloading,
+    Paused,
+    Verifying,
+    Extracting,
+    BackingUp,
+    RemovingOld,
+    Installing,
+    Finalizing,
+    CleaningUp,
+    Completed,
+}
+
+/// Sự kiện tiến trình gửi lên giao diện (`translation-progress`, `updater-progress`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub operation_id: String,
+    pub phase: ProgressPhase,
+    pub bytes_done: u64,
+    pub bytes_total: u64,  // 0 = giai đoạn không tính theo byte
+    pub bytes_per_second: u64,
+    pub eta_secs: Option<u64>,
+    pub current_file: Option<String>,
+}
+
+/// Sự kiện `download-paused-changed` khi tạm dừng hoặc tiếp tục lượt tải của một thao tác
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadPausedEvent {
+    pub operation_id: String,
+    pub paused: bool,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub path: String,  // Đường dẫn tương đối so với thư mục gốc bản việt hóa (dùng '/')
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Danh sách file kèm hash của một phiên bản việt hóa (asset manifest.json trên release)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationManifest {
+    pub version: String,
+    pub files: Vec<ManifestFile>,
+}
+
+impl TranslationManifest {
+    pub fn get_file(&self, path: &str) -> Option<&ManifestFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+}
+
+/// Kết quả kiểm tra file bản việt hóa đang cài so với manifest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub version: String,
+    pub checked: usize,
+    pub missing: Vec<String>,
+    pub modified: Vec<String>,  // Sai hash (kể cả file đã áp dụng bản sửa của người dùng)
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty()
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationOverride {
+    pub source: String,       // Câu gốc tiếng Nhật
+    pub translation: String,  // Bản dịch của người dùng
+    #[serde(default)]
+    pub pack_translation: Option<String>,  // Bản dịch gốc của bản việt hóa, dùng để khôi phục khi xóa
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OverrideReport {
+    pub applied: usize,         // Số dòng đã thay trong file của bản việt hóa
+    pub appended: usize,        // Số câu không có trong bản việt hóa, ghi vào file riêng
+    pub obsolete: Vec<String>,  // Câu mà bản việt hóa mới đã dịch giống bản sửa
+}
@@ -1,5 +1,9 @@
+use crate::models::ArchiveFormat;
 use serde::{Deserialize, Serialize};
 
+/// Phần mở rộng của file chữ ký minisign đi kèm asset (ví dụ `PriconneTL_20260211-VH.zip.minisig`)
+pub const SIGNATURE_EXTENSION: &str = "minisig";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranslationVersion {
     pub version: String,
@@ -8,6 +12,16 @@ pub struct TranslationVersion {
     pub file_size: u64,
     pub changelog: Vec<String>,
     pub download_count: u64,  // Số lượt tải
+    #[serde(default)]
+    pub manifest_url: Option<String>,  // manifest.json dùng cho cập nhật delta
+    #[serde(default)]
+    pub signature_url: Option<String>,  // Chữ ký minisign của asset
+    #[serde(default)]
+    pub manifest_signature_url: Option<String>,  // Chữ ký minisign của manifest.json
+    #[serde(default)]
+    pub asset_name: String,
+    #[serde(default)]
+    pub sha256: Option<String>,  // Hash của asset do GitHub cung cấp, dùng để kiểm tra file tải từ mirror
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,19 +49,80 @@ impl TranslationPack {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitHubRelease {
+/// Release theo định dạng API của GitHub (Gitea/Forgejo và manifest tĩnh dùng cùng định dạng)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
     pub tag_name: String,
+    #[serde(default)]
     pub name: String,
     pub published_at: String,
+    #[serde(default)]
     pub body: String,
-    pub assets: Vec<GitHubAsset>,
+    pub assets: Vec<ReleaseFile>,
+}
+
+impl Release {
+    /// Chuyển release thành phiên bản việt hóa, None nếu release không có file nén bản việt hóa
+    pub fn into_translation_version(self) -> Option<TranslationVersion> {
+        let asset = self.assets.iter().find(|a| {
+            ArchiveFormat::from_file_name(&a.name).is_some() && a.name.contains("PriconneTL")
+        })?;
+
+        // Tìm asset manifest.json (danh sách hash từng file) dùng cho cập nhật delta
+        let manifest_url = self.assets.iter()
+            .find(|a| a.name == "manifest.json")
+            .map(|a| a.browser_download_url.clone());
+
+        // Chữ ký minisign đi kèm: <tên asset>.minisig
+        let find_signature = |name: &str| {
+            let signature_name = format!("{}.{}", name, SIGNATURE_EXTENSION);
+            self.assets.iter()
+                .find(|a| a.name == signature_name)
+                .map(|a| a.browser_download_url.clone())
+        };
+        let signature_url = find_signature(&asset.name);
+        let manifest_signature_url = find_signature("manifest.json");
+
+        Some(TranslationVersion {
+            version: self.tag_name.clone(),
+            release_date: self.published_at.clone(),
+            download_url: asset.browser_download_url.clone(),
+            file_size: asset.size,
+            changelog: self.changelog(),
+            download_count: asset.download_count,
+            manifest_url,
+            signature_url,
+            manifest_signature_url,
+            asset_name: asset.name.clone(),
+            sha256: asset.sha256(),
+        })
+    }
+
+    /// Parse changelog từ body
+    pub fn changelog(&self) -> Vec<String> {
+        self.body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .collect()
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitHubAsset {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseFile {
     pub name: String,
     pub size: u64,
     pub browser_download_url: String,
+    #[serde(default)]
     pub download_count: u64,  // Số lượt tải
+    #[serde(default)]
+    pub digest: Option<String>,  // Dạng "sha256:<hex>", chỉ có với asset tải lên gần đây
+}
+
+impl ReleaseFile {
+    pub fn sha256(&self) -> Option<String> {
+        self.digest.as_deref()
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+            .map(|hash| hash.to_lowercase())
+    }
 }
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchField {
+    Source,       // Câu gốc tiếng Nhật
+    Translation,  // Bản dịch tiếng Việt
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    Plain,
+    Fuzzy,
+    Regex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationSearchResult {
+    pub file: String,  // Đường dẫn tương đối so với thư mục game
+    pub line: usize,
+    pub source: String,
+    pub translation: String,
+    pub score: i64,
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Manifest cập nhật app (latest.json), mỗi nền tảng một file cài đặt có chữ ký riêng
+///
+/// Khóa của `platforms` có dạng `<os>-<arch>[-<loại>]`, ví dụ `windows-x86_64-msi`,
+/// `windows-x86_64-nsis`, `linux-x86_64-appimage`, `darwin-aarch64-dmg`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateManifest {
+    pub version: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub pub_date: String,
+    pub platforms: BTreeMap<String, UpdatePlatform>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePlatform {
+    pub url: String,
+    pub signature: String,  // Nội dung file .minisig (hoặc base64 của nó như `tauri signer`)
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallerKind {
+    Msi,
+    Nsis,
+    AppImage,
+    Dmg,
+}
+
+impl InstallerKind {
+    /// Xác định loại file cài đặt từ tên file
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".msi") {
+            Some(InstallerKind::Msi)
+        } else if name.ends_with(".exe") {
+            Some(InstallerKind::Nsis)
+        } else if name.ends_with(".appimage") {
+            Some(InstallerKind::AppImage)
+        } else if name.ends_with(".dmg") {
+            Some(InstallerKind::Dmg)
+        } else {
+            None
+        }
+    }
+
+    /// Tên dùng trong khóa nền tảng của manifest
+    pub fn platform_suffix(&self) -> &'static str {
+        match self {
+            InstallerKind::Msi => "msi",
+            InstallerKind::Nsis => "nsis",
+            InstallerKind::AppImage => "appimage",
+            InstallerKind::Dmg => "dmg",
+        }
+    }
+}
+
+/// Bản cập nhật đã tải và kiểm tra xong, sẽ được cài khi khởi động lại app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingUpdate {
+    pub version: String,
+    pub installer_kind: InstallerKind,
+    pub path: PathBuf,
+    pub staged_at: String,
+}
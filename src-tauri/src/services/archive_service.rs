@@ -0,0 +1,583 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, ArchiveEntry, ArchiveFormat, ExtractLimits};
+use crate::services::FileService;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// File nhỏ hơn mức này có thể nén rất tốt (file rỗng, đệm) nên không xét tỉ lệ nén
+const MIN_RATIO_CHECK_SIZE: u64 = 1024 * 1024;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const ZIP_EMPTY_MAGIC: &[u8] = b"PK\x05\x06";
+const SEVEN_ZIP_MAGIC: &[u8] = b"7z\xBC\xAF\x27\x1C";
+const ZSTD_MAGIC: &[u8] = b"\x28\xB5\x2F\xFD";
+
+pub struct ArchiveService;
+
+impl ArchiveService {
+    /// Xác định định dạng từ magic bytes ở đầu file, không dựa vào phần mở rộng
+    pub fn detect_format(path: &Path) -> Result<ArchiveFormat, AppError> {
+        let mut file = fs::File::open(path)
+            .map_err(|e| err!("error.open_archive", error = e))?;
+
+        let mut header = Vec::with_capacity(6);
+        (&mut file).take(6).read_to_end(&mut header)
+            .map_err(|e| err!("error.read_archive", error = e))?;
+
+        if header.starts_with(ZIP_MAGIC) || header.starts_with(ZIP_EMPTY_MAGIC) {
+            Ok(ArchiveFormat::Zip)
+        } else if header.starts_with(SEVEN_ZIP_MAGIC) {
+            Ok(ArchiveFormat::SevenZip)
+        } else if header.starts_with(ZSTD_MAGIC) {
+            Ok(ArchiveFormat::TarZst)
+        } else {
+            Err(err!("error.unsupported_archive", path = path.display()))
+        }
+    }
+
+    /// Liệt kê các mục trong file nén
+    pub fn list(path: &Path) -> Result<Vec<ArchiveEntry>, AppError> {
+        match Self::detect_format(path)? {
+            ArchiveFormat::Zip => Self::list_zip(path),
+            ArchiveFormat::SevenZip => Self::list_7z(path),
+            ArchiveFormat::TarZst => Self::list_tar_zst(path),
+        }
+    }
+
+    /// Giải nén với giới hạn trong cài đặt
+    pub fn extract<F>(path: &Path, extract_to: &Path, progress_callback: F) -> Result<(), AppError>
+    where
+        F: FnMut(u64, u64),
+    {
+        let limits = AppConfig::load()
+            .map(|config| config.extract_limits)
+            .unwrap_or_default();
+
+        Self::extract_with_limits(path, extract_to, &limits, progress_callback)
+    }
+
+    /// Giải nén file nén, kiểm tra toàn bộ archive trước khi ghi
+    ///
+    /// Báo lỗi (thay vì bỏ qua) với đường dẫn không an toàn và symlink; từ chối archive
+    /// vượt giới hạn số file, dung lượng, tỉ lệ nén hoặc không đủ dung lượng trống.
+    /// `progress_callback` nhận số byte đã giải nén và tổng số byte.
+    #[tracing::instrument(skip(limits, progress_callback))]
+    pub fn extract_with_limits<F>(
+        path: &Path,
+        extract_to: &Path,
+        limits: &ExtractLimits,
+        mut progress_callback: F,
+    ) -> Result<(), AppError>
+    where
+        F: FnMut(u64, u64),
+    {
+        let format = Self::detect_format(path)?;
+        let entries = Self::list(path)?;
+
+        let archive_size = fs::metadata(path)
+            .map(|m| m.len())
+            .map_err(|e| err!("error.read_archive", error = e))?;
+        let total_size = Self::check_entries(&entries, archive_size, limits)?;
+
+        // Tạo thư mục đích nếu chưa có
+        fs::create_dir_all(extract_to)
+            .map_err(|e| err!("error.create_extract_dir", error = e))?;
+
+        if !FileService::check_disk_space(extract_to, total_size)? {
+            return Err(err!("error.extract_disk_space", bytes = total_size));
+        }
+
+        let mut extracted: u64 = 0;
+        let mut on_entry = |written: u64| {
+            extracted = extracted.saturating_add(written);
+            progress_callback(extracted, total_size);
+        };
+
+        match format {
+            ArchiveFormat::Zip => Self::extract_zip(path, extract_to, &mut on_entry)?,
+            ArchiveFormat::SevenZip => Self::extract_7z(path, extract_to, &mut on_entry)?,
+            ArchiveFormat::TarZst => Self::extract_tar_zst(path, extract_to, &mut on_entry)?,
+        }
+
+        tracing::info!(format = ?format, entries = entries.len(), bytes = total_size, "Extracted archive");
+        Ok(())
+    }
+
+    /// Kiểm tra tên, loại và kích thước khai báo của mọi mục, trả về tổng dung lượng sau giải nén
+    pub fn check_entries(entries: &[ArchiveEntry], archive_size: u64, limits: &ExtractLimits) -> Result<u64, AppError> {
+        if entries.len() > limits.max_entries {
+            return Err(err!("error.archive_too_many_entries", count = entries.len(), limit = limits.max_entries));
+        }
+
+        let mut total_size: u64 = 0;
+        for entry in entries {
+            Self::entry_path(&entry.name)?;
+
+            if entry.is_link {
+                return Err(err!("error.archive_symlink", name = entry.name));
+            }
+
+            if let Some(compressed_size) = entry.compressed_size {
+                if entry.size > MIN_RATIO_CHECK_SIZE && entry.size / compressed_size.max(1) > limits.max_ratio {
+                    return Err(err!("error.archive_entry_ratio", name = entry.name));
+                }
+            }
+
+            total_size = total_size.saturating_add(entry.size);
+            if total_size > limits.max_total_size {
+                return Err(err!("error.archive_too_large", limit = limits.max_total_size));
+            }
+        }
+
+        // 7z solid và tar.zst không có kích thước nén từng file nên xét tỉ lệ của cả archive
+        if total_size > MIN_RATIO_CHECK_SIZE && total_size / archive_size.max(1) > limits.max_ratio {
+            return Err(err!("error.archive_ratio"));
+        }
+
+        Ok(total_size)
+    }
+
+    /// Đường dẫn tương đối an toàn của một mục, None với mục gốc ("./")
+    fn entry_path(name: &str) -> Result<Option<PathBuf>, AppError> {
+        let normalized = name.replace('\\', "/");
+        if normalized.starts_with('/') || normalized.contains(':') {
+            return Err(err!("error.archive_unsafe_path", name = name));
+        }
+
+        let parts: Vec<&str> = normalized.split('/')
+            .filter(|part| !part.is_empty() && *part != ".")
+            .collect();
+        if parts.is_empty() {
+            return Ok(None);
+        }
+
+        FileService::safe_relative_path(&parts.join("/"))
+            .map(Some)
+            .map_err(|_| err!("error.archive_unsafe_path", name = name))
+    }
+
+    /// Ghi một file ra đĩa, không tin kích thước khai báo
+    fn write_file(outpath: &Path, reader: &mut dyn Read, declared_size: u64, name: &str) -> Result<u64, AppError> {
+        if let Some(parent) = outpath.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| err!("error.create_dir", error = e))?;
+        }
+
+        let mut outfile = fs::File::create(outpath)
+            .map_err(|e| err!("error.create_file", error = e))?;
+
+        // Đọc tối đa thêm 1 byte để phát hiện file lớn hơn khai báo
+        let written = std::io::copy(&mut reader.take(declared_size + 1), &mut outfile)
+            .map_err(|e| err!("error.extract_file", error = e))?;
+        if written > declared_size {
+            return Err(err!("error.archive_entry_size", name = name));
+        }
+
+        Ok(written)
+    }
+
+    /// Chỉ giữ quyền thực thi, bỏ setuid/setgid/sticky và quyền ghi cho người khác
+    #[cfg(unix)]
+    fn set_mode(outpath: &Path, mode: Option<u32>) -> Result<(), AppError> {
+        use std::os::unix::fs::PermissionsExt;
+        if let Some(mode) = mode {
+            let mode = if mode & 0o111 != 0 { 0o755 } else { 0o644 };
+            fs::set_permissions(outpath, fs::Permissions::from_mode(mode))
+                .map_err(|e| err!("error.set_permissions", error = e))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn set_mode(_outpath: &Path, _mode: Option<u32>) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    fn list_zip(path: &Path) -> Result<Vec<ArchiveEntry>, AppError> {
+        let file = fs::File::open(path)
+            .map_err(|e| err!("error.open_zip", error = e))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| err!("error.read_zip", error = e))?;
+
+        let mut entries = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            let file = archive.by_index_raw(i)
+                .map_err(|e| err!("error.read_archive_file", error = e))?;
+            entries.push(ArchiveEntry {
+                name: file.name().to_string(),
+                size: file.size(),
+                compressed_size: Some(file.compressed_size()),
+                is_dir: file.is_dir(),
+                is_link: file.is_symlink(),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn extract_zip(path: &Path, extract_to: &Path, on_entry: &mut dyn FnMut(u64)) -> Result<(), AppError> {
+        let file = fs::File::open(path)
+            .map_err(|e| err!("error.open_zip", error = e))?;
+        let mut archive = zip::ZipArchive::new(file)
+            .map_err(|e| err!("error.read_zip", error = e))?;
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)
+                .map_err(|e| err!("error.read_archive_file", error = e))?;
+
+            let outpath = match Self::entry_path(file.name())? {
+                Some(relative) => extract_to.join(relative),
+                None => continue,
+            };
+
+            if file.is_dir() {
+                fs::create_dir_all(&outpath)
+                    .map_err(|e| err!("error.create_dir", error = e))?;
+                continue;
+            }
+
+            let name = file.name().to_string();
+            let declared_size = file.size();
+            let written = Self::write_file(&outpath, &mut file, declared_size, &name)?;
+            Self::set_mode(&outpath, file.unix_mode())?;
+            on_entry(written);
+        }
+
+        Ok(())
+    }
+
+    /// Mode unix (nếu có) lưu ở 16 bit cao của thuộc tính Windows khi bật cờ 0x8000
+    fn sevenz_unix_mode(entry: &sevenz_rust::SevenZArchiveEntry) -> Option<u32> {
+        if entry.has_windows_attributes && entry.windows_attributes & 0x8000 != 0 {
+            Some(entry.windows_attributes >> 16)
+        } else {
+            None
+        }
+    }
+
+    fn list_7z(path: &Path) -> Result<Vec<ArchiveEntry>, AppError> {
+        let archive = sevenz_rust::Archive::open(path)
+            .map_err(|e| err!("error.read_7z", error = e))?;
+
+        Ok(archive.files.iter().map(|file| {
+            // 0x400 = reparse point (symlink/junction trên Windows)
+            let is_reparse_point = file.has_windows_attributes && file.windows_attributes & 0x400 != 0;
+            let is_unix_link = Self::sevenz_unix_mode(file)
+                .map(|mode| mode & 0o170000 == 0o120000)
+                .unwrap_or(false);
+
+            ArchiveEntry {
+                name: file.name.clone(),
+                size: file.size,
+                compressed_size: None,
+                is_dir: file.is_directory,
+                is_link: is_reparse_point || is_unix_link,
+            }
+        }).collect())
+    }
+
+    fn extract_7z(path: &Path, extract_to: &Path, on_entry: &mut dyn FnMut(u64)) -> Result<(), AppError> {
+        let mut reader = sevenz_rust::SevenZReader::open(path, sevenz_rust::Password::empty())
+            .map_err(|e| err!("error.read_7z", error = e))?;
+
+        reader.for_each_entries(|entry, data| {
+            // Trả lỗi để dừng hẳn, Ok(false) chỉ dừng block hiện tại
+            let result = (|| -> Result<(), AppError> {
+                let outpath = match Self::entry_path(&entry.name)? {
+                    Some(relative) => extract_to.join(relative),
+                    None => return Ok(()),
+                };
+
+                if entry.is_directory {
+                    return fs::create_dir_all(&outpath)
+                        .map_err(|e| err!("error.create_dir", error = e));
+                }
+
+                let written = Self::write_file(&outpath, data, entry.size, &entry.name)?;
+                Self::set_mode(&outpath, Self::sevenz_unix_mode(entry))?;
+                on_entry(written);
+                Ok(())
+            })();
+
+            result.map(|_| true).map_err(|e| sevenz_rust::Error::other(e.to_string()))
+        }).map_err(|e| err!("error.extract_7z", error = e))
+    }
+
+    fn open_tar_zst(path: &Path) -> Result<tar::Archive<zstd::Decoder<'static, std::io::BufReader<fs::File>>>, AppError> {
+        let file = fs::File::open(path)
+            .map_err(|e| err!("error.open_archive", error = e))?;
+        let decoder = zstd::Decoder::new(file)
+            .map_err(|e| err!("error.read_zstd", error = e))?;
+        Ok(tar::Archive::new(decoder))
+    }
+
+    /// Tên mục trong tar, dùng "/" làm dấu phân cách
+    fn tar_entry_name<R: Read>(entry: &tar::Entry<R>) -> String {
+        String::from_utf8_lossy(&entry.path_bytes()).into_owned()
+    }
+
+    fn list_tar_zst(path: &Path) -> Result<Vec<ArchiveEntry>, AppError> {
+        // tar không có mục lục nên phải giải nén cả luồng một lượt để liệt kê
+        let mut archive = Self::open_tar_zst(path)?;
+        let mut entries = Vec::new();
+
+        for entry in archive.entries().map_err(|e| err!("error.read_tar", error = e))? {
+            let entry = entry.map_err(|e| err!("error.read_archive_file", error = e))?;
+            let entry_type = entry.header().entry_type();
+
+            // Bỏ qua các mục metadata (PAX, GNU long name) vì crate tar đã xử lý
+            if entry_type.is_pax_global_extensions()
+                || entry_type.is_pax_local_extensions()
+                || entry_type.is_gnu_longname()
+                || entry_type.is_gnu_longlink()
+            {
+                continue;
+            }
+
+            entries.push(ArchiveEntry {
+                name: Self::tar_entry_name(&entry),
+                size: entry.size(),
+                compressed_size: None,
+                is_dir: entry_type.is_dir(),
+                is_link: !entry_type.is_dir() && !entry_type.is_file(),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn extract_tar_zst(path: &Path, extract_to: &Path, on_entry: &mut dyn FnMut(u64)) -> Result<(), AppError> {
+        let mut archive = Self::open_tar_zst(path)?;
+
+        for entry in archive.entries().map_err(|e| err!("error.read_tar", error = e))? {
+            let mut entry = entry.map_err(|e| err!("error.read_archive_file", error = e))?;
+            let entry_type = entry.header().entry_type();
+            let name = Self::tar_entry_name(&entry);
+
+            if entry_type.is_dir() {
+                if let Some(relative) = Self::entry_path(&name)? {
+                    fs::create_dir_all(extract_to.join(relative))
+                        .map_err(|e| err!("error.create_dir", error = e))?;
+                }
+                continue;
+            }
+            if !entry_type.is_file() {
+                // Đã kiểm tra lúc liệt kê, chỉ còn mục metadata
+                continue;
+            }
+
+            let outpath = match Self::entry_path(&name)? {
+                Some(relative) => extract_to.join(relative),
+                None => continue,
+            };
+
+            let declared_size = entry.size();
+            let mode = entry.header().mode().ok();
+            let written = Self::write_file(&outpath, &mut entry, declared_size, &name)?;
+            Self::set_mode(&outpath, mode)?;
+            on_entry(written);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const FORMATS: [ArchiveFormat; 3] = [ArchiveFormat::Zip, ArchiveFormat::SevenZip, ArchiveFormat::TarZst];
+
+    enum Entry<'a> {
+        File(&'a str, &'a [u8]),
+        Symlink(&'a str, &'a str),
+    }
+
+    // Ghi archive bằng thư viện của từng định dạng, giữ nguyên tên mục (kể cả tên không an toàn)
+    fn build(dir: &Path, format: ArchiveFormat, entries: &[Entry]) -> PathBuf {
+        let path = dir.join(format!("pack.{}", format.extension()));
+        match format {
+            ArchiveFormat::Zip => {
+                let mut writer = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+                let options = zip::write::SimpleFileOptions::default();
+                for entry in entries {
+                    match entry {
+                        Entry::File(name, data) => {
+                            writer.start_file(*name, options).unwrap();
+                            writer.write_all(data).unwrap();
+                        }
+                        Entry::Symlink(name, target) => writer.add_symlink(*name, *target, options).unwrap(),
+                    }
+                }
+                writer.finish().unwrap();
+            }
+            ArchiveFormat::SevenZip => {
+                let mut writer = sevenz_rust::SevenZWriter::create(&path).unwrap();
+                for entry in entries {
+                    let mut archive_entry = sevenz_rust::SevenZArchiveEntry::new();
+                    let data: &[u8] = match entry {
+                        Entry::File(name, data) => {
+                            archive_entry.name = name.to_string();
+                            data
+                        }
+                        Entry::Symlink(name, target) => {
+                            // Symlink unix lưu mode ở 16 bit cao của thuộc tính Windows
+                            archive_entry.name = name.to_string();
+                            archive_entry.has_windows_attributes = true;
+                            archive_entry.windows_attributes = 0x8000 | (0o120777 << 16);
+                            target.as_bytes()
+                        }
+                    };
+                    archive_entry.has_stream = true;
+                    writer.push_archive_entry(archive_entry, Some(data)).unwrap();
+                }
+                writer.finish().unwrap();
+            }
+            ArchiveFormat::TarZst => {
+                let encoder = zstd::Encoder::new(fs::File::create(&path).unwrap(), 3).unwrap();
+                let mut builder = tar::Builder::new(encoder);
+                for entry in entries {
+                    let mut header = tar::Header::new_gnu();
+                    let (name, data): (&str, &[u8]) = match entry {
+                        Entry::File(name, data) => {
+                            header.set_entry_type(tar::EntryType::Regular);
+                            (name, data)
+                        }
+                        Entry::Symlink(name, target) => {
+                            header.set_entry_type(tar::EntryType::Symlink);
+                            header.set_link_name(target).unwrap();
+                            (name, &[])
+                        }
+                    };
+                    // set_path từ chối "..", ghi thẳng tên vào header
+                    header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+                    header.set_size(data.len() as u64);
+                    header.set_mode(0o644);
+                    header.set_cksum();
+                    builder.append(&header, data).unwrap();
+                }
+                builder.into_inner().unwrap().finish().unwrap();
+            }
+        }
+        path
+    }
+
+    fn extract(path: &Path, dir: &Path, limits: &ExtractLimits) -> Result<PathBuf, AppError> {
+        let extract_to = dir.join("out");
+        ArchiveService::extract_with_limits(path, &extract_to, limits, |_, _| {})?;
+        Ok(extract_to)
+    }
+
+    #[test]
+    fn extracts_safe_entries_in_every_format() {
+        for format in FORMATS {
+            let dir = tempfile::tempdir().unwrap();
+            let path = build(dir.path(), format, &[Entry::File("BepInEx/./config/a.cfg", b"x = 1")]);
+
+            assert_eq!(ArchiveService::detect_format(&path).unwrap(), format);
+            let out = extract(&path, dir.path(), &ExtractLimits::default()).unwrap();
+            assert_eq!(fs::read(out.join("BepInEx").join("config").join("a.cfg")).unwrap(), b"x = 1");
+        }
+    }
+
+    #[test]
+    fn rejects_unsafe_entry_names() {
+        for name in ["../evil.txt", "BepInEx/../../evil.txt", "..\\evil.txt", "/etc/evil.txt", "C:/evil.txt", "C:\\evil.txt", "\\\\server\\share\\evil.txt"] {
+            assert!(ArchiveService::entry_path(name).is_err(), "{}", name);
+        }
+        assert_eq!(ArchiveService::entry_path("./").unwrap(), None);
+
+        for format in FORMATS {
+            for name in ["../evil.txt", "/evil.txt", "C:/evil.txt"] {
+                let dir = tempfile::tempdir().unwrap();
+                let path = build(dir.path(), format, &[Entry::File(name, b"evil")]);
+
+                let error = extract(&path, dir.path(), &ExtractLimits::default()).unwrap_err();
+                assert_eq!(error, err!("error.archive_unsafe_path", name = name), "{:?}", format);
+                assert!(!dir.path().join("evil.txt").exists());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_symlinks_in_every_format() {
+        for format in FORMATS {
+            let dir = tempfile::tempdir().unwrap();
+            let path = build(dir.path(), format, &[
+                Entry::File("BepInEx/a.txt", b"a"),
+                Entry::Symlink("BepInEx/link", "/etc/passwd"),
+            ]);
+
+            let error = extract(&path, dir.path(), &ExtractLimits::default()).unwrap_err();
+            assert_eq!(error, err!("error.archive_symlink", name = "BepInEx/link"), "{:?}", format);
+            assert!(!dir.path().join("out").join("BepInEx").join("a.txt").exists());
+        }
+    }
+
+    #[test]
+    fn rejects_entry_larger_than_declared() {
+        let dir = tempfile::tempdir().unwrap();
+        let outpath = dir.path().join("a.txt");
+        assert_eq!(ArchiveService::write_file(&outpath, &mut &b"0123"[..], 4, "a.txt").unwrap(), 4);
+        assert!(ArchiveService::write_file(&outpath, &mut &b"0123456789"[..], 4, "a.txt").is_err());
+
+        // Zip khai báo kích thước giải nén nhỏ hơn dữ liệu thật (sửa cả local header và central directory)
+        let content = b"hello=xin chao\n".repeat(64);
+        let path = build(dir.path(), ArchiveFormat::Zip, &[Entry::File("a.txt", &content)]);
+        let mut bytes = fs::read(&path).unwrap();
+        let declared = 16u32.to_le_bytes();
+        bytes[22..26].copy_from_slice(&declared);
+        let central = bytes.windows(4).position(|window| window == b"PK\x01\x02").unwrap();
+        bytes[central + 24..central + 28].copy_from_slice(&declared);
+        fs::write(&path, bytes).unwrap();
+
+        let error = extract(&path, dir.path(), &ExtractLimits::default()).unwrap_err();
+        assert_eq!(error, err!("error.archive_entry_size", name = "a.txt"));
+    }
+
+    #[test]
+    fn enforces_limits_in_every_format() {
+        let zeros = vec![0u8; 4 * 1024 * 1024];
+        for format in FORMATS {
+            let dir = tempfile::tempdir().unwrap();
+            let path = build(dir.path(), format, &[Entry::File("a.txt", b"0123456789"), Entry::File("b.txt", b"0123456789")]);
+
+            let limits = ExtractLimits { max_entries: 1, ..ExtractLimits::default() };
+            let error = extract(&path, dir.path(), &limits).unwrap_err();
+            assert_eq!(error, err!("error.archive_too_many_entries", count = 2, limit = 1), "{:?}", format);
+
+            let limits = ExtractLimits { max_total_size: 15, ..ExtractLimits::default() };
+            let error = extract(&path, dir.path(), &limits).unwrap_err();
+            assert_eq!(error, err!("error.archive_too_large", limit = 15), "{:?}", format);
+
+            // 4MB số 0 nén còn vài KB, vượt tỉ lệ nén mặc định
+            let dir = tempfile::tempdir().unwrap();
+            let path = build(dir.path(), format, &[Entry::File("zeros.bin", &zeros)]);
+            let error = extract(&path, dir.path(), &ExtractLimits::default()).unwrap_err();
+            let expected = match format {
+                ArchiveFormat::Zip => err!("error.archive_entry_ratio", name = "zeros.bin"),
+                _ => err!("error.archive_ratio"),
+            };
+            assert_eq!(error, expected, "{:?}", format);
+            assert!(!dir.path().join("out").join("zeros.bin").exists());
+        }
+    }
+
+    #[test]
+    fn declared_sizes_are_checked_against_limits() {
+        let limits = ExtractLimits { max_total_size: 1024, ..ExtractLimits::default() };
+        let entry = |size: u64| ArchiveEntry {
+            name: "BepInEx/Translation/a.txt".to_string(),
+            size,
+            compressed_size: Some(size),
+            is_dir: false,
+            is_link: false,
+        };
+
+        assert!(ArchiveService::check_entries(&[entry(512)], 4096, &limits).is_ok());
+        assert!(ArchiveService::check_entries(&[entry(u64::MAX)], 4096, &limits).is_err());
+        assert!(ArchiveService::check_entries(&[entry(600), entry(600)], 4096, &limits).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use crate::err;
+use crate::models::{AppError, ConfigConflict};
+use crate::services::{FileService, PluginService};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Một file cfg/ini đã parse: (section, key) -> value
+struct ParsedConfig {
+    values: HashMap<(String, String), String>,
+    order: Vec<(String, String)>,
+}
+
+pub struct ConfigMergeService;
+
+impl ConfigMergeService {
+    /// Merge config của người dùng vào config của bản việt hóa mới vừa cài
+    ///
+    /// - `user_dir`: bản sao BepInEx/config của người dùng trước khi cập nhật
+    /// - `new_dir`: BepInEx/config sau khi copy bản việt hóa mới
+    /// - `base_dir`: config mặc định của bản việt hóa cũ (có thể không tồn tại)
+    #[tracing::instrument]
+    pub fn merge_config_dir(
+        user_dir: &Path,
+        new_dir: &Path,
+        base_dir: &Path,
+    ) -> Result<Vec<ConfigConflict>, AppError> {
+        let mut conflicts = Vec::new();
+
+        for file in PluginService::collect_plugin_files(user_dir)? {
+            let user_path = user_dir.join(&file);
+            let new_path = new_dir.join(&file);
+
+            // File không có trong bản mới (vd: config của plugin người dùng) thì giữ nguyên
+            if !new_path.exists() {
+                if let Some(parent) = new_path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| err!("error.create_dir", error = e))?;
+                }
+                fs::copy(&user_path, &new_path)
+                    .map_err(|e| err!("error.restore_config_file", file = file, error = e))?;
+                continue;
+            }
+
+            if !Self::is_mergeable(&file) {
+                continue;
+            }
+
+            let user_content = fs::read_to_string(&user_path)
+                .map_err(|e| err!("error.read_config_file", file = file, error = e))?;
+            let new_content = fs::read_to_string(&new_path)
+                .map_err(|e| err!("error.read_config_file", file = file, error = e))?;
+            let base_content = fs::read_to_string(base_dir.join(&file)).ok();
+
+            if user_content == new_content {
+                continue;
+            }
+
+            let (merged, file_conflicts) = Self::merge_cfg(
+                &file,
+                base_content.as_deref(),
+                &user_content,
+                &new_content,
+            );
+
+            fs::write(&new_path, merged)
+                .map_err(|e| err!("error.write_config_file", file = file, error = e))?;
+            for conflict in &file_conflicts {
+                tracing::warn!(
+                    file = %conflict.file,
+                    section = %conflict.section,
+                    key = %conflict.key,
+                    "Config conflict, keeping user value"
+                );
+            }
+            conflicts.extend(file_conflicts);
+        }
+
+        Ok(conflicts)
+    }
+
+    /// Lưu config mặc định của bản việt hóa để làm base cho lần merge sau
+    pub fn save_pack_defaults(pack_config_dir: &Path, defaults_dir: &Path) -> Result<(), AppError> {
+        if defaults_dir.exists() {
+            FileService::remove_path(defaults_dir)?;
+        }
+
+        if pack_config_dir.exists() {
+            FileService::copy_dir_recursive(pack_config_dir, defaults_dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Merge 3 chiều một file cfg: base (mặc định cũ), user (hiện tại), new (mặc định mới)
+    pub fn merge_cfg(
+        file: &str,
+        base: Option<&str>,
+        user: &str,
+        new: &str,
+    ) -> (String, Vec<ConfigConflict>) {
+        let base_config = base.map(Self::parse);
+        let user_config = Self::parse(user);
+        let new_config = Self::parse(new);
+        let line_ending = if new.contains("\r\n") { "\r\n" } else { "\n" };
+
+        let mut conflicts = Vec::new();
+        let mut output: Vec<String> = Vec::new();
+        let mut section = String::new();
+
+        // Key người dùng tự thêm (không có trong base) mà bản mới không có thì giữ lại
+        let extra_keys: Vec<&(String, String)> = user_config.order.iter()
+            .filter(|id| !new_config.values.contains_key(*id))
+            .filter(|id| match &base_config {
+                Some(base) => !base.values.contains_key(*id),
+                None => true,
+            })
+            .collect();
+
+        let flush_extra = |section: &str, output: &mut Vec<String>| {
+            for (extra_section, key) in &extra_keys {
+                if extra_section == section {
+                    let value = &user_config.values[&(extra_section.clone(), key.clone())];
+                    output.push(format!("{} = {}", key, value));
+                }
+            }
+        };
+
+        for line in new.lines() {
+            let trimmed = line.trim();
+
+            if let Some(name) = Self::parse_section(trimmed) {
+                Self::insert_before_trailing_blank(&mut output, |output| flush_extra(&section, output));
+                section = name;
+                output.push(line.to_string());
+                continue;
+            }
+
+            let Some((key, new_value)) = Self::parse_key_value(trimmed) else {
+                output.push(line.to_string());
+                continue;
+            };
+
+            let id = (section.clone(), key.clone());
+            let user_value = user_config.values.get(&id);
+            let base_value = base_config.as_ref().and_then(|base| base.values.get(&id));
+
+            let value = match user_value {
+                // Người dùng không có key này: dùng mặc định mới
+                None => new_value.clone(),
+                Some(user_value) if *user_value == new_value => new_value.clone(),
+                Some(user_value) => match base_value {
+                    // Người dùng không sửa: dùng mặc định mới
+                    Some(base_value) if base_value == user_value => new_value.clone(),
+                    // Người dùng sửa, mặc định không đổi: giữ giá trị người dùng
+                    Some(base_value) if *base_value == new_value => user_value.clone(),
+                    // Cả hai cùng đổi, hoặc không có base nên không biết người dùng có sửa không:
+                    // giữ giá trị người dùng và báo xung đột để mặc định mới không bị bỏ qua âm thầm
+                    _ => {
+                        conflicts.push(ConfigConflict {
+                            file: file.to_string(),
+                            section: section.clone(),
+                            key: key.clone(),
+                            base_value: base_value.cloned(),
+                            user_value: user_value.clone(),
+                            new_value: new_value.clone(),
+                        });
+                        user_value.clone()
+                    }
+                },
+            };
+
+            output.push(Self::replace_value(line, &value));
+        }
+
+        Self::insert_before_trailing_blank(&mut output, |output| flush_extra(&section, output));
+
+        // Section chỉ có ở file của người dùng
+        let mut extra_sections: Vec<&String> = Vec::new();
+        for (extra_section, _) in &extra_keys {
+            // Key không thuộc section nào đã được chèn ở đầu file
+            let exists = extra_section.is_empty()
+                || new.lines().any(|l| Self::parse_section(l.trim()).as_ref() == Some(extra_section));
+            if !exists && !extra_sections.contains(&extra_section) {
+                extra_sections.push(extra_section);
+            }
+        }
+        for extra_section in extra_sections {
+            output.push(String::new());
+            output.push(format!("[{}]", extra_section));
+            flush_extra(extra_section, &mut output);
+        }
+
+        let mut merged = output.join(line_ending);
+        merged.push_str(line_ending);
+        (merged, conflicts)
+    }
+
+    /// File cfg/ini có thể merge theo từng key
+    pub fn is_mergeable(file: &str) -> bool {
+        let lower = file.to_lowercase();
+        lower.ends_with(".cfg") || lower.ends_with(".ini")
+    }
+
+    fn parse(content: &str) -> ParsedConfig {
+        let mut parsed = ParsedConfig {
+            values: HashMap::new(),
+            order: Vec::new(),
+        };
+        let mut section = String::new();
+
+        for line in content.lines() {
+            let trimmed = line.trim();
+
+            if let Some(name) = Self::parse_section(trimmed) {
+                section = name;
+            } else if let Some((key, value)) = Self::parse_key_value(trimmed) {
+                let id = (section.clone(), key);
+                if parsed.values.insert(id.clone(), value).is_none() {
+                    parsed.order.push(id);
+                }
+            }
+        }
+
+        parsed
+    }
+
+    fn parse_section(line: &str) -> Option<String> {
+        if line.starts_with('[') && line.ends_with(']') {
+            Some(line[1..line.len() - 1].trim().to_string())
+        } else {
+            None
+        }
+    }
+
+    fn parse_key_value(line: &str) -> Option<(String, String)> {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            return None;
+        }
+
+        let (key, value) = line.split_once('=')?;
+        Some((key.trim().to_string(), value.trim().to_string()))
+    }
+
+    /// Thay giá trị trong dòng `Key = Value`, giữ nguyên định dạng phần key
+    fn replace_value(line: &str, value: &str) -> String {
+        match line.split_once('=') {
+            Some((key_part, rest)) => {
+                let spacing = if rest.starts_with(' ') { " " } else { "" };
+                format!("{}={}{}", key_part, spacing, value)
+            }
+            None => line.to_string(),
+        }
+    }
+
+    /// Chèn thêm dòng trước các dòng trống ở cuối section
+    fn insert_before_trailing_blank<F>(output: &mut Vec<String>, insert: F)
+    where
+        F: Fn(&mut Vec<String>),
+    {
+        let mut trailing = Vec::new();
+        while output.last().map(|l| l.trim().is_empty()).unwrap_or(false) {
+            trailing.push(output.pop().unwrap());
+        }
+        insert(output);
+        output.extend(trailing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(base: Option<&str>, user: &str, new: &str) -> (String, Vec<ConfigConflict>) {
+        ConfigMergeService::merge_cfg("Test.cfg", base, user, new)
+    }
+
+    #[test]
+    fn keeps_user_change_when_default_unchanged() {
+        let (merged, conflicts) = merge(Some("[A]\nx = 1\n"), "[A]\nx = 2\n", "[A]\nx = 1\n");
+        assert_eq!(merged, "[A]\nx = 2\n");
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn takes_new_default_when_user_unchanged() {
+        let (merged, conflicts) = merge(Some("[A]\nx = 1\n"), "[A]\nx = 1\n", "[A]\nx = 3\n");
+        assert_eq!(merged, "[A]\nx = 3\n");
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn reports_conflict_when_both_changed() {
+        let (merged, conflicts) = merge(Some("[A]\nx = 1\n"), "[A]\nx = 2\n", "[A]\nx = 3\n");
+        assert_eq!(merged, "[A]\nx = 2\n");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].section, "A");
+        assert_eq!(conflicts[0].key, "x");
+        assert_eq!(conflicts[0].base_value.as_deref(), Some("1"));
+        assert_eq!(conflicts[0].user_value, "2");
+        assert_eq!(conflicts[0].new_value, "3");
+    }
+
+    #[test]
+    fn handles_added_and_removed_keys() {
+        let base = "[A]\nx = 1\nold = 1\n";
+        let user = "[A]\nx = 1\nold = 1\nmine = 5\n";
+        let new = "[A]\nx = 1\nadded = 7\n";
+
+        let (merged, conflicts) = merge(Some(base), user, new);
+        // Key bản mới bỏ thì bỏ, key người dùng tự thêm thì giữ, key bản mới thêm thì dùng mặc định
+        assert_eq!(merged, "[A]\nx = 1\nadded = 7\nmine = 5\n");
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn keeps_user_sections_and_pack_comments() {
+        let base = "## Cài đặt\n[A]\nx = 1\n";
+        let user = "[A]\nx = 1\n\n[Extra]\ny = 2\n";
+        let new = "## Cài đặt mới\n[A]\n# Mô tả\nx = 1\n\n[B]\nz = 3\n";
+
+        let (merged, conflicts) = merge(Some(base), user, new);
+        assert_eq!(merged, "## Cài đặt mới\n[A]\n# Mô tả\nx = 1\n\n[B]\nz = 3\n\n[Extra]\ny = 2\n");
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn reports_changed_default_without_base() {
+        let (merged, conflicts) = merge(None, "[A]\nx = 2\ny = 1\n", "[A]\nx = 3\ny = 1\n");
+        assert_eq!(merged, "[A]\nx = 2\ny = 1\n");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].key, "x");
+        assert_eq!(conflicts[0].base_value, None);
+        assert_eq!(conflicts[0].new_value, "3");
+    }
+}
@@ -0,0 +1,570 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, ArchiveEntry, ConfigConflict, ManifestFile, TranslationManifest, VerifyReport};
+use crate::services::{ArchiveService, ConfigMergeService, DownloadService, FileService};
+use flate2::read::DeflateDecoder;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+const INSTALLED_MANIFEST: &str = "translation_manifest.json";
+const EOCD_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x05, 0x06];
+const CENTRAL_HEADER_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x01, 0x02];
+const LOCAL_HEADER_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x03, 0x04];
+const EOCD_MIN_SIZE: u64 = 22;
+const EOCD_MAX_SEARCH: u64 = EOCD_MIN_SIZE + u16::MAX as u64;
+// Gộp các đoạn cần tải nếu cách nhau ít hơn 64KB để giảm số request
+const RANGE_MERGE_GAP: u64 = 64 * 1024;
+
+/// Các file cần thay đổi giữa bản đang cài và bản mới
+#[derive(Debug, Default)]
+pub struct DeltaPlan {
+    pub changed: Vec<ManifestFile>,
+    pub removed: Vec<String>,
+}
+
+/// Vị trí một file trong zip theo central directory
+struct ZipEntryLocation {
+    name: String,
+    header_offset: u64,
+    method: u16,
+    compressed_size: u64,
+    uncompressed_size: u64,
+}
+
+pub struct DeltaService;
+
+impl DeltaService {
+    /// Đọc manifest của bản việt hóa đang cài
+    pub fn load_installed_manifest(game_path: &Path) -> Option<TranslationManifest> {
+        let content = fs::read_to_string(game_path.join(INSTALLED_MANIFEST)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Lưu manifest của bản việt hóa vừa cài
+    pub fn save_installed_manifest(game_path: &Path, manifest: &TranslationManifest) -> Result<(), AppError> {
+        let content = serde_json::to_string_pretty(manifest)
+            .map_err(|e| err!("error.serialize_translation_manifest", error = e))?;
+
+        fs::write(game_path.join(INSTALLED_MANIFEST), content)
+            .map_err(|e| err!("error.write_translation_manifest", error = e))
+    }
+
+    /// Xóa manifest khi gỡ bản việt hóa
+    pub fn remove_installed_manifest(game_path: &Path) -> Result<(), AppError> {
+        FileService::remove_path(&game_path.join(INSTALLED_MANIFEST))
+    }
+
+    /// So sánh hash các file trong thư mục game với manifest đã lưu lúc cài
+    ///
+    /// Bỏ qua BepInEx/config vì config được merge với thay đổi của người dùng.
+    /// Plugin hoặc dxgi.dll đang bị tắt (đuôi `.disabled`) vẫn được tính là có.
+    #[tracing::instrument]
+    pub fn verify_installed(game_path: &Path) -> Result<VerifyReport, AppError> {
+        let manifest = Self::load_installed_manifest(game_path)
+            .ok_or_else(|| err!("error.translation_manifest_missing"))?;
+
+        let mut report = VerifyReport {
+            version: manifest.version.clone(),
+            ..Default::default()
+        };
+
+        for file in &manifest.files {
+            if file.path.starts_with("BepInEx/config/") {
+                continue;
+            }
+            report.checked += 1;
+
+            let path = game_path.join(&file.path);
+            let disabled_path = game_path.join(format!("{}.disabled", file.path));
+            let actual_path = if path.is_file() {
+                path
+            } else if disabled_path.is_file() {
+                disabled_path
+            } else {
+                report.missing.push(file.path.clone());
+                continue;
+            };
+
+            if !FileService::sha256_file(&actual_path)?.eq_ignore_ascii_case(&file.sha256) {
+                report.modified.push(file.path.clone());
+            }
+        }
+
+        tracing::info!(
+            checked = report.checked,
+            missing = report.missing.len(),
+            modified = report.modified.len(),
+            "Verification finished"
+        );
+        Ok(report)
+    }
+
+    /// Tạo manifest từ thư mục bản việt hóa đã giải nén
+    pub fn build_manifest(version: &str, translation_root: &Path) -> Result<TranslationManifest, AppError> {
+        let mut files = Vec::new();
+
+        for path in crate::services::PluginService::collect_plugin_files(translation_root)? {
+            let full_path = translation_root.join(&path);
+            let size = fs::metadata(&full_path)
+                .map_err(|e| err!("error.file_metadata", error = e))?
+                .len();
+
+            files.push(ManifestFile {
+                sha256: FileService::sha256_file(&full_path)?,
+                path,
+                size,
+            });
+        }
+
+        Ok(TranslationManifest {
+            version: version.to_string(),
+            files,
+        })
+    }
+
+    /// So sánh manifest đang cài với manifest bản mới
+    pub fn plan(installed: &TranslationManifest, target: &TranslationManifest) -> DeltaPlan {
+        let installed_files: HashMap<&str, &ManifestFile> = installed.files.iter()
+            .map(|f| (f.path.as_str(), f))
+            .collect();
+
+        let changed = target.files.iter()
+            .filter(|f| match installed_files.get(f.path.as_str()) {
+                Some(old) => !old.sha256.eq_ignore_ascii_case(&f.sha256),
+                None => true,
+            })
+            .cloned()
+            .collect();
+
+        let removed = installed.files.iter()
+            .filter(|f| target.get_file(&f.path).is_none())
+            .map(|f| f.path.clone())
+            .collect();
+
+        DeltaPlan { changed, removed }
+    }
+
+    /// Tải các file thay đổi bằng HTTP Range từ zip của release
+    ///
+    /// Đọc central directory ở cuối zip, chỉ tải đoạn byte của các file cần thiết
+    /// rồi giải nén trực tiếp các file đó vào `staging_dir`.
+    #[tracing::instrument(skip(download_service, plan, staging_dir, progress_callback), fields(files = plan.changed.len()))]
+    pub async fn download_changed_files<F>(
+        download_service: &DownloadService,
+        archive_url: &str,
+        archive_size: u64,
+        plan: &DeltaPlan,
+        staging_dir: &Path,
+        mut progress_callback: F,
+    ) -> Result<(), AppError>
+    where
+        F: FnMut(u64, u64) + Send,
+    {
+        if plan.changed.is_empty() {
+            return Ok(());
+        }
+
+        let archive_size = if archive_size > 0 {
+            archive_size
+        } else {
+            download_service.get_file_size(archive_url).await?
+        };
+        if archive_size < EOCD_MIN_SIZE {
+            return Err(err!("error.archive_size_unknown"));
+        }
+
+        // Đọc phần cuối file để tìm End of Central Directory
+        let tail_start = archive_size - archive_size.min(EOCD_MAX_SEARCH);
+        let tail = download_service
+            .fetch_range(archive_url, tail_start, archive_size - 1, &mut |_, _| {})
+            .await?
+            .ok_or_else(|| err!("error.range_unsupported"))?;
+
+        let (cd_offset, cd_size) = Self::parse_end_of_central_directory(&tail)?;
+        if cd_size == 0 || cd_offset + cd_size > archive_size {
+            return Err(err!("error.invalid_central_directory"));
+        }
+        let central_directory = if cd_offset >= tail_start {
+            let start = (cd_offset - tail_start) as usize;
+            tail.get(start..start + cd_size as usize)
+                .ok_or_else(|| err!("error.invalid_central_directory"))?
+                .to_vec()
+        } else {
+            download_service
+                .fetch_range(archive_url, cd_offset, cd_offset + cd_size - 1, &mut |_, _| {})
+                .await?
+                .ok_or_else(|| err!("error.range_unsupported"))?
+        };
+
+        let entries = Self::parse_central_directory(&central_directory)?;
+
+        // Zip có thể có thư mục wrapper (PriconneTL_YYYYMMDD-VH/BepInEx/...)
+        let prefix = entries.iter()
+            .find_map(|e| e.name.find("BepInEx/").map(|i| e.name[..i].to_string()))
+            .unwrap_or_default();
+
+        let mut offsets: Vec<u64> = entries.iter().map(|e| e.header_offset).collect();
+        offsets.push(cd_offset);
+        offsets.sort_unstable();
+
+        // Mỗi file nằm từ local header đến đầu file kế tiếp
+        let mut wanted = Vec::new();
+        for file in &plan.changed {
+            FileService::safe_relative_path(&file.path)?;
+            let entry_name = format!("{}{}", prefix, file.path);
+            let entry = entries.iter()
+                .find(|e| e.name == entry_name)
+                .ok_or_else(|| err!("error.archive_file_not_found", file = file.path))?;
+
+            let end = Self::entry_end(&offsets, entry, cd_offset)?;
+            wanted.push((file, entry, end));
+        }
+
+        // Kích thước khai báo phải nằm trong giới hạn giải nén trước khi tải,
+        // khi giải nén cũng không đọc quá kích thước khai báo
+        let limits = AppConfig::load()
+            .map(|config| config.extract_limits)
+            .unwrap_or_default();
+        let declared: Vec<ArchiveEntry> = wanted.iter()
+            .map(|(_, entry, _)| ArchiveEntry {
+                name: entry.name.clone(),
+                size: entry.uncompressed_size,
+                compressed_size: Some(entry.compressed_size),
+                is_dir: false,
+                is_link: false,
+            })
+            .collect();
+        ArchiveService::check_entries(&declared, archive_size, &limits)?;
+
+        let mut ranges: Vec<(u64, u64)> = wanted.iter()
+            .map(|(_, entry, end)| (entry.header_offset, *end))
+            .collect();
+        ranges.sort_unstable();
+
+        // Không gộp quá `DownloadService::MAX_RANGE_SIZE` vì mỗi đoạn được giữ trong bộ nhớ
+        let mut merged: Vec<(u64, u64)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last)
+                    if start <= last.1 + RANGE_MERGE_GAP
+                        && end.max(last.1) - last.0 <= DownloadService::MAX_RANGE_SIZE =>
+                {
+                    last.1 = last.1.max(end)
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        let total: u64 = merged.iter().map(|(start, end)| end - start).sum();
+        let mut downloaded = 0u64;
+        let mut chunks = Vec::new();
+        progress_callback(downloaded, total);
+
+        for (start, end) in merged {
+            let bytes = download_service
+                .fetch_range(archive_url, start, end - 1, &mut |done, _| progress_callback(downloaded + done, total))
+                .await?
+                .ok_or_else(|| err!("error.range_unsupported"))?;
+
+            downloaded += bytes.len() as u64;
+            chunks.push((start, bytes));
+        }
+
+        // Giải nén và kiểm tra hash từng file
+        for (file, entry, end) in wanted {
+            let (chunk_start, chunk) = chunks.iter()
+                .find(|(start, bytes)| *start <= entry.header_offset && end <= start + bytes.len() as u64)
+                .ok_or_else(|| err!("error.missing_data", file = file.path))?;
+            let local = &chunk[(entry.header_offset - chunk_start) as usize..(end - chunk_start) as usize];
+
+            let content = Self::decompress_entry(local, entry)?;
+            let hash = format!("{:x}", Sha256::digest(&content));
+            if !hash.eq_ignore_ascii_case(&file.sha256) {
+                return Err(err!("error.hash_mismatch", file = file.path));
+            }
+
+            let out_path = staging_dir.join(&file.path);
+            if let Some(parent) = out_path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| err!("error.create_dir", error = e))?;
+            }
+            fs::write(&out_path, content)
+                .map_err(|e| err!("error.extract_file", error = e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Giải nén dữ liệu một file từ local header (chỉ hỗ trợ stored và deflate)
+    ///
+    /// Không tin `uncompressed_size`: không cấp phát trước theo nó và dừng khi dữ liệu vượt quá nó.
+    fn decompress_entry(local: &[u8], entry: &ZipEntryLocation) -> Result<Vec<u8>, AppError> {
+        if local.len() < 30 || local[0..4] != LOCAL_HEADER_SIGNATURE {
+            return Err(err!("error.invalid_local_header", file = entry.name));
+        }
+
+        let name_length = u16::from_le_bytes([local[26], local[27]]) as usize;
+        let extra_length = u16::from_le_bytes([local[28], local[29]]) as usize;
+        let data_start = 30 + name_length + extra_length;
+        let data = local.get(data_start..data_start + entry.compressed_size as usize)
+            .ok_or_else(|| err!("error.truncated_data", file = entry.name))?;
+
+        match entry.method {
+            0 if data.len() as u64 > entry.uncompressed_size => {
+                Err(err!("error.archive_entry_size", name = entry.name))
+            }
+            0 => Ok(data.to_vec()),
+            8 => {
+                // Đọc tối đa thêm 1 byte để phát hiện file lớn hơn khai báo
+                let mut content = Vec::new();
+                DeflateDecoder::new(data)
+                    .take(entry.uncompressed_size + 1)
+                    .read_to_end(&mut content)
+                    .map_err(|e| err!("error.extract_file", error = e))?;
+                if content.len() as u64 > entry.uncompressed_size {
+                    return Err(err!("error.archive_entry_size", name = entry.name));
+                }
+                Ok(content)
+            }
+            method => Err(err!("error.unsupported_compression", method = method, file = entry.name)),
+        }
+    }
+
+    /// Áp dụng các file đã tải vào thư mục game
+    #[tracing::instrument(skip(staging_dir, plan), fields(changed = plan.changed.len(), removed = plan.removed.len()))]
+    pub fn apply(game_path: &Path, staging_dir: &Path, plan: &DeltaPlan) -> Result<Vec<ConfigConflict>, AppError> {
+        let defaults_dir = game_path.join("translation_defaults").join("config");
+        let mut conflicts = Vec::new();
+
+        for file in &plan.changed {
+            let staged = staging_dir.join(&file.path);
+            let mut dest = game_path.join(&file.path);
+
+            if let Some(config_file) = file.path.strip_prefix("BepInEx/config/") {
+                if dest.exists() && ConfigMergeService::is_mergeable(config_file) {
+                    let user = fs::read_to_string(&dest)
+                        .map_err(|e| err!("error.read_config_file", file = config_file, error = e))?;
+                    let new = fs::read_to_string(&staged)
+                        .map_err(|e| err!("error.read_config_file", file = config_file, error = e))?;
+                    let base = fs::read_to_string(defaults_dir.join(config_file)).ok();
+
+                    let (merged, file_conflicts) = ConfigMergeService::merge_cfg(
+                        config_file,
+                        base.as_deref(),
+                        &user,
+                        &new,
+                    );
+                    fs::write(&dest, merged)
+                        .map_err(|e| err!("error.write_config_file", file = config_file, error = e))?;
+                    conflicts.extend(file_conflicts);
+                } else {
+                    Self::copy_file(&staged, &dest)?;
+                }
+
+                // Cập nhật config mặc định cho lần merge sau
+                Self::copy_file(&staged, &defaults_dir.join(config_file))?;
+                continue;
+            }
+
+            // Plugin đang bị tắt thì vẫn giữ trạng thái tắt
+            if file.path.starts_with("BepInEx/plugins/") && !dest.exists() {
+                let disabled = game_path.join(format!("{}.disabled", file.path));
+                if disabled.exists() {
+                    dest = disabled;
+                }
+            }
+
+            Self::copy_file(&staged, &dest)?;
+        }
+
+        for path in &plan.removed {
+            FileService::safe_relative_path(path)?;
+
+            // Config của người dùng được giữ lại, chỉ xóa bản mặc định
+            if let Some(config_file) = path.strip_prefix("BepInEx/config/") {
+                FileService::remove_path(&defaults_dir.join(config_file))?;
+                continue;
+            }
+
+            FileService::remove_path(&game_path.join(path))?;
+            FileService::remove_path(&game_path.join(format!("{}.disabled", path)))?;
+        }
+
+        Ok(conflicts)
+    }
+
+    fn copy_file(source: &Path, dest: &Path) -> Result<(), AppError> {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| err!("error.create_dir", error = e))?;
+        }
+
+        fs::copy(source, dest)
+            .map_err(|e| err!("error.copy_file", error = e))?;
+
+        Ok(())
+    }
+
+    /// Trả về (offset, size) của central directory
+    /// Vị trí kết thúc dữ liệu của file: local header kế tiếp hoặc đầu central directory
+    ///
+    /// Central directory hỏng hoặc cố ý sửa có thể trỏ local header ra ngoài vùng dữ liệu.
+    fn entry_end(offsets: &[u64], entry: &ZipEntryLocation, cd_offset: u64) -> Result<u64, AppError> {
+        if entry.header_offset >= cd_offset {
+            return Err(err!("error.invalid_central_directory_entry"));
+        }
+
+        let end = offsets.iter()
+            .copied()
+            .find(|offset| *offset > entry.header_offset)
+            .unwrap_or(cd_offset);
+        if end <= entry.header_offset {
+            return Err(err!("error.invalid_central_directory_entry"));
+        }
+
+        Ok(end)
+    }
+
+    fn parse_end_of_central_directory(tail: &[u8]) -> Result<(u64, u64), AppError> {
+        if tail.len() < EOCD_MIN_SIZE as usize {
+            return Err(err!("error.eocd_not_found"));
+        }
+
+        let position = (0..=tail.len().saturating_sub(EOCD_MIN_SIZE as usize))
+            .rev()
+            .find(|&i| tail[i..i + 4] == EOCD_SIGNATURE)
+            .ok_or_else(|| err!("error.eocd_not_found"))?;
+
+        let record = &tail[position..];
+        let entries = u16::from_le_bytes([record[10], record[11]]);
+        let cd_size = u32::from_le_bytes([record[12], record[13], record[14], record[15]]);
+        let cd_offset = u32::from_le_bytes([record[16], record[17], record[18], record[19]]);
+
+        if entries == u16::MAX || cd_size == u32::MAX || cd_offset == u32::MAX {
+            return Err(err!("error.zip64_unsupported"));
+        }
+
+        Ok((cd_offset as u64, cd_size as u64))
+    }
+
+    fn parse_central_directory(data: &[u8]) -> Result<Vec<ZipEntryLocation>, AppError> {
+        let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+        let read_u32 = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+        let mut entries = Vec::new();
+        let mut position = 0usize;
+
+        while position + 46 <= data.len() {
+            if data[position..position + 4] != CENTRAL_HEADER_SIGNATURE {
+                return Err(err!("error.invalid_central_directory_entry"));
+            }
+
+            let name_length = read_u16(position + 28);
+            let extra_length = read_u16(position + 30);
+            let comment_length = read_u16(position + 32);
+            let compressed_size = read_u32(position + 20);
+            let uncompressed_size = read_u32(position + 24);
+            let header_offset = read_u32(position + 42);
+
+            if header_offset == u32::MAX || compressed_size == u32::MAX || uncompressed_size == u32::MAX {
+                return Err(err!("error.zip64_unsupported"));
+            }
+
+            let name_bytes = data.get(position + 46..position + 46 + name_length)
+                .ok_or_else(|| err!("error.invalid_central_directory_entry"))?;
+
+            entries.push(ZipEntryLocation {
+                name: String::from_utf8_lossy(name_bytes).replace('\\', "/"),
+                header_offset: header_offset as u64,
+                method: read_u16(position + 10) as u16,
+                compressed_size: compressed_size as u64,
+                uncompressed_size: uncompressed_size as u64,
+            });
+
+            position += 46 + name_length + extra_length + comment_length;
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    // Local header tối thiểu (30 byte + tên) rồi tới dữ liệu đã nén
+    fn local_entry(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut local = LOCAL_HEADER_SIGNATURE.to_vec();
+        local.extend_from_slice(&[0u8; 22]);
+        local.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes());
+        local.extend_from_slice(name.as_bytes());
+        local.extend_from_slice(data);
+        local
+    }
+
+    fn deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn location(name: &str, method: u16, compressed_size: usize, uncompressed_size: u64) -> ZipEntryLocation {
+        ZipEntryLocation {
+            name: name.to_string(),
+            header_offset: 0,
+            method,
+            compressed_size: compressed_size as u64,
+            uncompressed_size,
+        }
+    }
+
+    #[test]
+    fn inflates_entry_within_declared_size() {
+        let content = b"hello=xin chao\n".repeat(100);
+        let compressed = deflate(&content);
+        let local = local_entry("a.txt", &compressed);
+
+        let entry = location("a.txt", 8, compressed.len(), content.len() as u64);
+        assert_eq!(DeltaService::decompress_entry(&local, &entry).unwrap(), content);
+    }
+
+    #[test]
+    fn stops_inflating_past_declared_size() {
+        // 64MB số 0 nén còn vài chục KB nhưng khai báo chỉ 1KB
+        let compressed = deflate(&vec![0u8; 64 * 1024 * 1024]);
+        let local = local_entry("bomb.txt", &compressed);
+
+        let entry = location("bomb.txt", 8, compressed.len(), 1024);
+        assert!(DeltaService::decompress_entry(&local, &entry).is_err());
+
+        let stored = local_entry("stored.txt", b"0123456789");
+        assert!(DeltaService::decompress_entry(&stored, &location("stored.txt", 0, 10, 4)).is_err());
+    }
+
+    #[test]
+    fn rejects_entries_outside_data_area() {
+        let offsets = [0, 100, 200];
+        let at = |header_offset: u64| ZipEntryLocation { header_offset, ..location("a.txt", 0, 0, 0) };
+
+        assert_eq!(DeltaService::entry_end(&offsets, &at(0), 200).unwrap(), 100);
+        assert_eq!(DeltaService::entry_end(&offsets, &at(100), 200).unwrap(), 200);
+        assert!(DeltaService::entry_end(&offsets, &at(200), 200).is_err());
+        assert!(DeltaService::entry_end(&offsets, &at(500), 200).is_err());
+    }
+
+    #[test]
+    fn rejects_tail_shorter_than_end_record() {
+        assert!(DeltaService::parse_end_of_central_directory(&[]).is_err());
+        assert!(DeltaService::parse_end_of_central_directory(&EOCD_SIGNATURE).is_err());
+
+        let mut record = EOCD_SIGNATURE.to_vec();
+        record.extend_from_slice(&[0u8; 18]);
+        assert_eq!(DeltaService::parse_end_of_central_directory(&record).unwrap(), (0, 0));
+    }
+
+}
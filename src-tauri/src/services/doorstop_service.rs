@@ -0,0 +1,111 @@
+use crate::err;
+use crate::models::AppError;
+use crate::services::GameService;
+use std::fs;
+use std::path::Path;
+
+const CONFIG_FILE: &str = "doorstop_config.ini";
+const LOADER_FILE: &str = "dxgi.dll";
+const DISABLED_LOADER_FILE: &str = "dxgi.dll.disabled";
+
+/// Bật/tắt Doorstop (trình nạp BepInEx) để chuyển nhanh giữa bản việt hóa và bản gốc
+pub struct DoorstopService;
+
+impl DoorstopService {
+    /// Doorstop có đang được bật không
+    pub fn is_enabled(game_path: &Path) -> bool {
+        if !game_path.join(LOADER_FILE).exists() {
+            return false;
+        }
+
+        fs::read_to_string(game_path.join(CONFIG_FILE))
+            .ok()
+            .and_then(|content| Self::read_enabled(&content))
+            .unwrap_or(true)
+    }
+
+    /// Bản việt hóa đã cài nhưng đang bị người dùng tắt
+    pub fn is_disabled(game_path: &Path) -> bool {
+        let installed = game_path.join(LOADER_FILE).exists() || game_path.join(DISABLED_LOADER_FILE).exists();
+        installed && !Self::is_enabled(game_path)
+    }
+
+    #[tracing::instrument]
+    pub fn set_enabled(game_path: &Path, enabled: bool) -> Result<(), AppError> {
+        GameService::ensure_game_not_running()?;
+
+        let loader_path = game_path.join(LOADER_FILE);
+        let disabled_loader_path = game_path.join(DISABLED_LOADER_FILE);
+        let config_path = game_path.join(CONFIG_FILE);
+
+        if !loader_path.exists() && !disabled_loader_path.exists() {
+            return Err(err!("error.translation_not_installed"));
+        }
+
+        // Khôi phục dxgi.dll nếu trước đó bị đổi tên
+        if enabled && !loader_path.exists() {
+            fs::rename(&disabled_loader_path, &loader_path)
+                .map_err(|e| err!("error.restore_named", file = LOADER_FILE, error = e))?;
+        }
+
+        if config_path.exists() {
+            let content = fs::read_to_string(&config_path)
+                .map_err(|e| err!("error.read_named", file = CONFIG_FILE, error = e))?;
+
+            if let Some(updated) = Self::write_enabled(&content, enabled) {
+                fs::write(&config_path, updated)
+                    .map_err(|e| err!("error.write_named", file = CONFIG_FILE, error = e))?;
+                return Ok(());
+            }
+        }
+
+        // Không có config (hoặc không có khóa enabled) thì đổi tên dxgi.dll
+        if !enabled && loader_path.exists() {
+            if disabled_loader_path.exists() {
+                fs::remove_file(&disabled_loader_path)
+                    .map_err(|e| err!("error.remove_named", file = DISABLED_LOADER_FILE, error = e))?;
+            }
+            fs::rename(&loader_path, &disabled_loader_path)
+                .map_err(|e| err!("error.disable_named", file = LOADER_FILE, error = e))?;
+        }
+
+        Ok(())
+    }
+
+    fn read_enabled(content: &str) -> Option<bool> {
+        content.lines().find_map(|line| {
+            let (key, value) = Self::parse_entry(line)?;
+            (key == "enabled").then(|| value.eq_ignore_ascii_case("true"))
+        })
+    }
+
+    /// Sửa giá trị `enabled`, giữ nguyên các dòng khác (comment, section, xuống dòng)
+    fn write_enabled(content: &str, enabled: bool) -> Option<String> {
+        let mut found = false;
+
+        let updated: String = content
+            .split_inclusive('\n')
+            .map(|line| match Self::parse_entry(line) {
+                Some(("enabled", _)) if !found => {
+                    found = true;
+                    let ending = &line[line.trim_end_matches(['\r', '\n']).len()..];
+                    let indent = &line[..line.len() - line.trim_start().len()];
+                    format!("{}enabled = {}{}", indent, enabled, ending)
+                }
+                _ => line.to_string(),
+            })
+            .collect();
+
+        found.then_some(updated)
+    }
+
+    fn parse_entry(line: &str) -> Option<(&str, &str)> {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') || line.starts_with('[') {
+            return None;
+        }
+
+        let (key, value) = line.split_once('=')?;
+        Some((key.trim(), value.trim()))
+    }
+}
@@ -0,0 +1,193 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, QueueStatus, QueuedDownload, QueuedOperation};
+use crate::services::{DownloadService, FileService};
+use crate::t;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+static QUEUE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Hàng đợi các lượt tải chưa xong, lưu trong `download_queue.json` để tải tiếp sau khi khởi động lại
+pub struct DownloadQueueService;
+
+impl DownloadQueueService {
+    /// Thư mục chứa file đang tải dở
+    pub fn downloads_dir() -> Result<PathBuf, AppError> {
+        let dir = AppConfig::get_data_dir()?.join("downloads");
+        fs::create_dir_all(&dir)
+            .map_err(|e| err!("error.create_downloads_dir", error = e))?;
+        Ok(dir)
+    }
+
+    /// Thêm lượt tải vào hàng đợi, trả về id (tên file tải dở)
+    ///
+    /// Nếu file đã có trong hàng đợi thì dùng lại mục cũ để tải tiếp.
+    pub fn enqueue(
+        operation: QueuedOperation,
+        asset_name: &str,
+        file_path: &Path,
+        total_bytes: u64,
+    ) -> Result<String, AppError> {
+        let _lock = QUEUE_LOCK.lock().unwrap();
+
+        let id = file_path.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| err!("error.invalid_download_path", path = file_path.display()))?;
+        let now = chrono::Utc::now().to_rfc3339();
+
+        let mut entries = Self::load()?;
+        match entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                entry.operation = operation;
+                entry.status = QueueStatus::Downloading;
+                entry.total_bytes = total_bytes;
+                entry.error = None;
+                entry.updated_at = now;
+            }
+            None => entries.push(QueuedDownload {
+                id: id.clone(),
+                operation,
+                asset_name: asset_name.to_string(),
+                file_path: file_path.to_path_buf(),
+                status: QueueStatus::Downloading,
+                downloaded_bytes: 0,
+                total_bytes,
+                error: None,
+                created_at: now.clone(),
+                updated_at: now,
+            }),
+        }
+
+        Self::save(&entries)?;
+        tracing::info!(id, "Download queued");
+        Ok(id)
+    }
+
+    /// Đánh dấu lượt tải bị lỗi, file tải dở được giữ lại
+    pub fn mark_interrupted(id: &str, error: &AppError) {
+        let result = Self::update(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|entry| entry.id == id) {
+                entry.status = QueueStatus::Interrupted;
+                entry.error = Some(error.to_string());
+                entry.updated_at = chrono::Utc::now().to_rfc3339();
+            }
+        });
+        if let Err(e) = result {
+            tracing::warn!(id, error = %e, "Failed to update download queue");
+        }
+    }
+
+    /// Bỏ lượt tải đã xong khỏi hàng đợi, file đã tải do nơi gọi xử lý
+    pub fn complete(id: &str) {
+        if let Err(e) = Self::update(|entries| entries.retain(|entry| entry.id != id)) {
+            tracing::warn!(id, error = %e, "Failed to update download queue");
+        }
+    }
+
+    /// Bỏ lượt tải và xóa file tải dở
+    pub fn abandon(id: &str) -> Result<(), AppError> {
+        let mut removed = None;
+        Self::update(|entries| {
+            if let Some(index) = entries.iter().position(|entry| entry.id == id) {
+                removed = Some(entries.remove(index));
+            }
+        })?;
+
+        let entry = removed.ok_or_else(|| err!("error.queue_entry_not_found", id = id))?;
+        DownloadService::discard_partial(&entry.file_path)?;
+        tracing::info!(id, "Download abandoned");
+        Ok(())
+    }
+
+    /// Các lượt tải trong hàng đợi kèm số byte đã tải
+    pub fn list() -> Result<Vec<QueuedDownload>, AppError> {
+        let _lock = QUEUE_LOCK.lock().unwrap();
+
+        let mut entries = Self::load()?;
+        for entry in &mut entries {
+            if let Some(partial) = DownloadService::load_partial(&entry.file_path) {
+                entry.downloaded_bytes = partial.downloaded();
+                entry.total_bytes = partial.total_size;
+            }
+        }
+
+        Ok(entries)
+    }
+
+    pub fn get(id: &str) -> Result<Option<QueuedDownload>, AppError> {
+        Ok(Self::list()?.into_iter().find(|entry| entry.id == id))
+    }
+
+    /// Gọi lúc khởi động: lượt tải còn đang chạy nghĩa là app đã bị tắt giữa chừng,
+    /// file trong thư mục tải không thuộc hàng đợi thì xóa
+    pub fn recover() -> Result<(), AppError> {
+        let mut known = Vec::new();
+        Self::update(|entries| {
+            for entry in entries.iter_mut() {
+                if entry.status == QueueStatus::Downloading {
+                    entry.status = QueueStatus::Interrupted;
+                    entry.error = Some(t!("error.closed_during_download"));
+                }
+                known.push(entry.id.clone());
+            }
+        })?;
+
+        let downloads_dir = Self::downloads_dir()?;
+        let files = fs::read_dir(&downloads_dir)
+            .map_err(|e| err!("error.read_downloads_dir", error = e))?;
+        for file in files.flatten() {
+            let name = file.file_name().to_string_lossy().to_string();
+            let id = name.strip_suffix(".part.json").unwrap_or(&name);
+            if !known.iter().any(|known| known == id) {
+                tracing::info!(file = %name, "Removing orphaned download");
+                FileService::remove_path(&file.path())?;
+            }
+        }
+
+        if !known.is_empty() {
+            tracing::info!(count = known.len(), "Found interrupted downloads");
+        }
+        Ok(())
+    }
+
+    fn update(change: impl FnOnce(&mut Vec<QueuedDownload>)) -> Result<(), AppError> {
+        let _lock = QUEUE_LOCK.lock().unwrap();
+
+        let mut entries = Self::load()?;
+        change(&mut entries);
+        Self::save(&entries)
+    }
+
+    fn load() -> Result<Vec<QueuedDownload>, AppError> {
+        let queue_path = Self::get_queue_path()?;
+        if !queue_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&queue_path)
+            .map_err(|e| err!("error.read_download_queue", error = e))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| err!("error.parse_download_queue", error = e))
+    }
+
+    fn save(entries: &[QueuedDownload]) -> Result<(), AppError> {
+        let queue_path = Self::get_queue_path()?;
+
+        if let Some(parent) = queue_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| err!("error.create_config_dir", error = e))?;
+        }
+
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| err!("error.serialize_download_queue", error = e))?;
+
+        fs::write(&queue_path, content)
+            .map_err(|e| err!("error.write_download_queue", error = e))
+    }
+
+    fn get_queue_path() -> Result<PathBuf, AppError> {
+        Ok(AppConfig::get_data_dir()?.join("download_queue.json"))
+    }
+}
@@ -1,95 +1,983 @@
-use reqwest::Client;
-use std::path::PathBuf;
-use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use crate::err;
+use crate::models::{AppConfig, AppError, DownloadMirror, DownloadSegment, MirrorStat, PartialDownload, ReleaseAsset};
+use crate::services::{FileService, HttpClientService};
+use crate::t;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use futures_util::StreamExt;
 
+// Tên nguồn tải gốc (GitHub Releases) trong thống kê tốc độ
+const CANONICAL_SOURCE: &str = "github";
+const CANONICAL_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Giới hạn tốc độ tải (byte/s, 0 là không giới hạn), u64::MAX là chưa đọc từ cài đặt
+static RATE_LIMIT: AtomicU64 = AtomicU64::new(u64::MAX);
+// Các thao tác đang tải theo operation id, mỗi thao tác tạm dừng và tiếp tục riêng
+static OPERATIONS: Mutex<BTreeMap<String, DownloadOperation>> = Mutex::new(BTreeMap::new());
+
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+// Số lần nối lại bằng Range khi kết nối bị ngắt giữa chừng (thường gặp sau khi tạm dừng lâu)
+const MAX_RESUME_ATTEMPTS: u32 = 3;
+
+// Chỉ chia đoạn file lớn, file nhỏ tải một luồng nhanh hơn
+const SEGMENT_MIN_SIZE: u64 = 8 * 1024 * 1024;
+const MAX_CONNECTIONS: u32 = 16;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+const STATE_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Tải file qua HTTP client dùng chung (proxy, CA, timeout theo cài đặt mạng)
+///
+/// URL file:// chỉ được đọc khi nằm trong thư mục của nguồn release trên máy (`local_roots`).
+///
+/// Tạm dừng theo operation id của thao tác (`for_operation`), không ảnh hưởng thao tác khác đang tải.
+#[derive(Clone)]
 pub struct DownloadService {
-    client: Client,
+    local_roots: Vec<PathBuf>,
+    operation_id: String,
 }
 
 impl DownloadService {
+    /// Độ dài tối đa của một đoạn tải bằng `fetch_range` (đoạn được giữ trong bộ nhớ)
+    pub const MAX_RANGE_SIZE: u64 = 256 * 1024 * 1024;
+
     pub fn new() -> Self {
+        Self::with_local_roots(Vec::new())
+    }
+
+    /// Cho phép đọc file:// trong các thư mục này (nguồn release hoặc manifest là thư mục trên máy)
+    pub fn with_local_roots(local_roots: Vec<PathBuf>) -> Self {
         Self {
-            client: Client::builder()
-                .user_agent("PriconneVH-Installer/1.0")
-                .build()
-                .unwrap(),
+            local_roots,
+            operation_id: String::new(),
         }
     }
 
+    /// Bản sao dùng cho một thao tác, tạm dừng và tiếp tục theo `operation_id` của thao tác đó
+    pub fn for_operation(&self, operation_id: &str) -> Self {
+        Self {
+            local_roots: self.local_roots.clone(),
+            operation_id: operation_id.to_string(),
+        }
+    }
+
+    /// Thư mục chứa file của URL file://, dùng làm `local_roots` cho nguồn là file manifest trên máy
+    pub fn file_url_dir(url: &str) -> Option<PathBuf> {
+        if !url.starts_with("file://") {
+            return None;
+        }
+
+        reqwest::Url::parse(url).ok()?
+            .to_file_path().ok()?
+            .parent()
+            .map(Path::to_path_buf)
+    }
+
+    /// Đặt giới hạn tốc độ tải (KB/s, 0 là không giới hạn), áp dụng ngay cho các lượt tải đang chạy
+    pub fn set_rate_limit(kbps: u64) {
+        RATE_LIMIT.store(kbps.saturating_mul(1024), Ordering::Relaxed);
+        tracing::info!(kbps, "Download rate limit changed");
+    }
+
+    /// Giới hạn tốc độ hiện tại theo byte/s, đọc từ cài đặt ở lần gọi đầu tiên
+    fn rate_limit() -> u64 {
+        let limit = RATE_LIMIT.load(Ordering::Relaxed);
+        if limit != u64::MAX {
+            return limit;
+        }
+
+        let kbps = AppConfig::load()
+            .map(|config| config.download_rate_limit_kbps)
+            .unwrap_or(0);
+        let limit = kbps.saturating_mul(1024);
+        let _ = RATE_LIMIT.compare_exchange(u64::MAX, limit, Ordering::Relaxed, Ordering::Relaxed);
+        RATE_LIMIT.load(Ordering::Relaxed)
+    }
+
+    /// Tạm dừng các lượt tải của một thao tác
+    pub fn pause(operation_id: &str) -> Result<(), AppError> {
+        let mut operations = OPERATIONS.lock().unwrap();
+        let operation = operations.get_mut(operation_id)
+            .ok_or_else(|| err!("error.no_active_download"))?;
+
+        operation.paused = true;
+        tracing::info!(operation_id, "Download paused");
+        Ok(())
+    }
+
+    /// Tiếp tục các lượt tải đã tạm dừng của một thao tác
+    pub fn resume(operation_id: &str) {
+        let mut operations = OPERATIONS.lock().unwrap();
+        if let Some(operation) = operations.get_mut(operation_id).filter(|operation| operation.paused) {
+            operation.paused = false;
+            tracing::info!(operation_id, "Download resumed");
+        }
+    }
+
+    pub fn is_paused(operation_id: &str) -> bool {
+        OPERATIONS.lock().unwrap()
+            .get(operation_id)
+            .is_some_and(|operation| operation.paused)
+    }
+
     /// Download file với progress callback
+    #[tracing::instrument(skip(self, dest_path, progress_callback))]
     pub async fn download_file<F>(
         &self,
         url: &str,
         dest_path: PathBuf,
         mut progress_callback: F,
-    ) -> Result<PathBuf, String>
+    ) -> Result<PathBuf, AppError>
+    where
+        F: FnMut(u64, u64) + Send + 'static,
+    {
+        self.download_to(url, &dest_path, None, &mut progress_callback).await?;
+        Ok(dest_path)
+    }
+
+    /// Tải asset của release: thử nguồn nhanh nhất lần trước, các mirror trong cài đặt, cuối cùng là GitHub
+    ///
+    /// File tải từ mirror phải khớp kích thước (và hash nếu GitHub có) của asset gốc.
+    #[tracing::instrument(skip(self, asset, dest_path, progress_callback), fields(asset = %asset.name, tag = %asset.tag))]
+    pub async fn download_release_asset<F>(
+        &self,
+        asset: &ReleaseAsset,
+        dest_path: PathBuf,
+        mut progress_callback: F,
+    ) -> Result<PathBuf, AppError>
     where
         F: FnMut(u64, u64) + Send + 'static,
+    {
+        let mirrors = AppConfig::load()
+            .map(|config| config.download_mirrors)
+            .unwrap_or_default();
+        let mut stats = Self::load_mirror_stats();
+        let mut errors = Vec::new();
+
+        for (name, url, timeout) in Self::download_sources(asset, &mirrors, &stats) {
+            let started = Instant::now();
+            let result = self.download_to(&url, &dest_path, Some(timeout), &mut progress_callback)
+                .await
+                .and_then(|(size, sha256)| Self::verify_asset(asset, size, &sha256).map(|_| size));
+
+            let updated_at = chrono::Utc::now().to_rfc3339();
+            match result {
+                Ok(size) => {
+                    let bytes_per_second = (size as f64 / started.elapsed().as_secs_f64().max(0.001)) as u64;
+                    tracing::info!(source = %name, bytes_per_second, "Release asset downloaded");
+                    stats.insert(name, MirrorStat { bytes_per_second, updated_at });
+                    Self::save_mirror_stats(&stats);
+                    return Ok(dest_path);
+                }
+                Err(e) => {
+                    tracing::warn!(source = %name, error = %e, "Download source failed");
+                    errors.push((name.clone(), e));
+                    stats.insert(name, MirrorStat { bytes_per_second: 0, updated_at });
+                }
+            }
+        }
+
+        Self::save_mirror_stats(&stats);
+        // Giữ lại file tải dở để lần sau tải tiếp
+        if Self::load_partial(&dest_path).is_none() {
+            let _ = tokio::fs::remove_file(&dest_path).await;
+        }
+        let message = errors.iter()
+            .map(|(name, e)| format!("{}: {}", name, e))
+            .collect::<Vec<_>>()
+            .join("; ");
+        // Nguồn cuối cùng lỗi vì sai hash/chữ ký thì mã lỗi là của lỗi đó chứ không phải lỗi mạng
+        Err(errors.iter().fold(err!("error.download_all_sources", errors = message), |error, (_, e)| error.caused_by(e)))
+    }
+
+    /// Tải về file, trả về kích thước và sha256
+    ///
+    /// `stall_timeout`: hủy nếu không nhận được dữ liệu trong khoảng thời gian này
+    async fn download_to<F>(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        stall_timeout: Option<Duration>,
+        progress_callback: &mut F,
+    ) -> Result<(u64, String), AppError>
+    where
+        F: FnMut(u64, u64),
     {
         // Tạo thư mục nếu chưa có
         if let Some(parent) = dest_path.parent() {
             tokio::fs::create_dir_all(parent)
                 .await
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
+                .map_err(|e| err!("error.create_dir", error = e))?;
         }
 
-        // Gửi request
-        let response = self.client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to start download: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("Download failed with status: {}", response.status()));
+        if let Some(source_path) = self.local_path(url)? {
+            return self.copy_local(&source_path, dest_path, progress_callback).await;
         }
 
+        let _active = ActiveDownload::start(&self.operation_id);
+
+        // Gửi request
+        let response = Self::send_request(url, 0, None, stall_timeout).await?;
+
         // Lấy tổng kích thước file
         let total_size = response.content_length().unwrap_or(0);
 
-        // Tạo file
-        let mut file = File::create(&dest_path)
+        // Server hỗ trợ Range thì tải theo đoạn: tải tiếp được file dở, file lớn thì tải song song
+        // nhiều đoạn vì CDN thường giới hạn tốc độ từng kết nối
+        if total_size > 0 && Self::accepts_ranges(&response) {
+            let etag = response.headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            drop(response);
+            let partial = Self::resumable_partial(dest_path, url, etag.as_deref(), total_size)
+                .unwrap_or_else(|| {
+                    let connections = if total_size >= SEGMENT_MIN_SIZE { Self::connections() } else { 1 };
+                    PartialDownload::split(url, etag, total_size, connections)
+                });
+            return self.download_segmented(url, dest_path, partial, stall_timeout, progress_callback).await;
+        }
+
+        // Tạo file, không tải tiếp được nên bỏ trạng thái tải dở cũ
+        Self::remove_partial_state(dest_path);
+        let mut file = File::create(dest_path)
             .await
-            .map_err(|e| format!("Failed to create file: {}", e))?;
+            .map_err(|e| err!("error.create_file", error = e))?;
 
-        // Download với streaming
+        // Download với streaming, tính hash trong lúc tải để không phải đọc lại file
         let mut downloaded: u64 = 0;
+        let mut hasher = Sha256::new();
         let mut stream = response.bytes_stream();
+        let mut throttle = Throttle::new();
+        let mut resume_attempts = 0;
+        let mut last_reported = Instant::now();
+
+        loop {
+            // Không tính thời gian tạm dừng vào stall timeout
+            if self.wait_while_paused(downloaded, total_size, progress_callback).await {
+                throttle.reset();
+            }
+
+            let chunk = match Self::with_timeout(stall_timeout, stream.next()).await? {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) if downloaded > 0 && resume_attempts < MAX_RESUME_ATTEMPTS => {
+                    resume_attempts += 1;
+                    tracing::warn!(error = %e, downloaded, attempt = resume_attempts, "Download interrupted, resuming");
+                    stream = Self::send_request(url, downloaded, None, stall_timeout).await?.bytes_stream();
+                    continue;
+                }
+                Some(Err(e)) => return Err(err!("error.downloading", error = e)),
+                None => break,
+            };
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk.map_err(|e| format!("Error while downloading: {}", e))?;
-            
             file.write_all(&chunk)
                 .await
-                .map_err(|e| format!("Failed to write to file: {}", e))?;
+                .map_err(|e| err!("error.write_file", error = e))?;
+            hasher.update(&chunk);
 
+            // Không báo sau mỗi chunk, chỉ báo theo chu kỳ
             downloaded += chunk.len() as u64;
-            progress_callback(downloaded, total_size);
+            if last_reported.elapsed() >= PROGRESS_INTERVAL {
+                progress_callback(downloaded, total_size);
+                last_reported = Instant::now();
+            }
+            if let Some(delay) = throttle.delay(chunk.len() as u64) {
+                tokio::time::sleep(delay).await;
+            }
         }
+        progress_callback(downloaded, total_size);
 
         file.flush()
             .await
-            .map_err(|e| format!("Failed to flush file: {}", e))?;
+            .map_err(|e| err!("error.flush_file", error = e))?;
 
-        Ok(dest_path)
+        tracing::info!(bytes = downloaded, path = %dest_path.display(), "Download finished");
+        Ok((downloaded, format!("{:x}", hasher.finalize())))
+    }
+
+    /// Gửi request tải file, có `start` > 0 hoặc `end` thì chỉ tải đoạn [start, end] bằng Range
+    async fn send_request(
+        url: &str,
+        start: u64,
+        end: Option<u64>,
+        stall_timeout: Option<Duration>,
+    ) -> Result<reqwest::Response, AppError> {
+        let ranged = start > 0 || end.is_some();
+        let mut request = HttpClientService::client().get(url);
+        if ranged {
+            let end = end.map(|end| end.to_string()).unwrap_or_default();
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-{}", start, end));
+        }
+
+        let response = Self::with_timeout(stall_timeout, request.send())
+            .await?
+            .map_err(|e| err!("error.start_download", error = e))?;
+
+        if !response.status().is_success() {
+            tracing::error!(status = %response.status(), "Download failed");
+            return Err(err!("error.download_status", status = response.status()));
+        }
+
+        if ranged {
+            let expected = format!("bytes {}-", start);
+            let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+                && response.headers()
+                    .get(reqwest::header::CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .is_some_and(|value| value.starts_with(&expected));
+            if !resumed {
+                return Err(err!("error.resume_unsupported"));
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Số kết nối tải song song theo cài đặt
+    fn connections() -> u32 {
+        AppConfig::load()
+            .map(|config| config.download_connections)
+            .unwrap_or(1)
+            .clamp(1, MAX_CONNECTIONS)
+    }
+
+    fn accepts_ranges(response: &reqwest::Response) -> bool {
+        response.headers()
+            .get(reqwest::header::ACCEPT_RANGES)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.eq_ignore_ascii_case("bytes"))
+    }
+
+    /// Tải file thành các đoạn song song, mỗi đoạn ghi thẳng vào vị trí của nó trong file
+    ///
+    /// Progress của các đoạn được cộng dồn và báo định kỳ qua callback. Trạng thái từng đoạn
+    /// được lưu vào `<file>.part.json` để tải tiếp nếu bị ngắt, xong thì xóa.
+    async fn download_segmented<F>(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        partial: PartialDownload,
+        stall_timeout: Option<Duration>,
+        progress_callback: &mut F,
+    ) -> Result<(u64, String), AppError>
+    where
+        F: FnMut(u64, u64),
+    {
+        let total_size = partial.total_size;
+        let resumed = partial.downloaded();
+        if resumed == 0 {
+            let file = File::create(dest_path)
+                .await
+                .map_err(|e| err!("error.create_file", error = e))?;
+            file.set_len(total_size)
+                .await
+                .map_err(|e| err!("error.allocate_file", error = e))?;
+        }
+
+        let offsets: Vec<AtomicU64> = partial.segments.iter()
+            .map(|segment| AtomicU64::new(segment.offset))
+            .collect();
+        let snapshot = || PartialDownload {
+            url: partial.url.clone(),
+            etag: partial.etag.clone(),
+            total_size,
+            segments: partial.segments.iter()
+                .zip(&offsets)
+                .map(|(segment, offset)| DownloadSegment {
+                    start: segment.start,
+                    end: segment.end,
+                    offset: offset.load(Ordering::Relaxed),
+                })
+                .collect(),
+        };
+        Self::save_partial(dest_path, &snapshot());
+
+        let throttle = std::sync::Mutex::new(Throttle::new());
+        let segments = partial.segments.iter()
+            .zip(&offsets)
+            .filter(|(segment, _)| segment.offset <= segment.end)
+            .map(|(segment, offset)| {
+                self.download_segment(url, dest_path, segment, offset, stall_timeout, &throttle)
+            });
+        let mut segments = std::pin::pin!(futures_util::future::try_join_all(segments));
+
+        tracing::info!(segments = partial.segments.len(), total_size, resumed, "Starting segmented download");
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+        let mut last_saved = Instant::now();
+        let mut reported = (resumed, false);
+        progress_callback(resumed, total_size);
+        loop {
+            tokio::select! {
+                result = &mut segments => {
+                    if let Err(e) = result {
+                        Self::save_partial(dest_path, &snapshot());
+                        return Err(e);
+                    }
+                    break;
+                }
+                _ = ticker.tick() => {
+                    let state = (snapshot().downloaded(), Self::is_paused(&self.operation_id));
+                    if state != reported {
+                        reported = state;
+                        progress_callback(state.0, total_size);
+                    }
+                    if last_saved.elapsed() >= STATE_SAVE_INTERVAL {
+                        Self::save_partial(dest_path, &snapshot());
+                        last_saved = Instant::now();
+                    }
+                }
+            }
+        }
+        progress_callback(total_size, total_size);
+        Self::remove_partial_state(dest_path);
+
+        // Các đoạn về không theo thứ tự nên phải đọc lại file để tính hash
+        let path = dest_path.to_path_buf();
+        let sha256 = tokio::task::spawn_blocking(move || FileService::sha256_file(&path))
+            .await
+            .map_err(|e| err!("error.hash_file", error = e))??;
+
+        tracing::info!(bytes = total_size, path = %dest_path.display(), "Download finished");
+        Ok((total_size, sha256))
+    }
+
+    /// Tải một đoạn vào đúng vị trí trong file, bắt đầu từ `offset` và cập nhật nó sau mỗi lần ghi
+    ///
+    /// Tự nối lại khi kết nối bị ngắt.
+    async fn download_segment(
+        &self,
+        url: &str,
+        dest_path: &Path,
+        segment: &DownloadSegment,
+        offset: &AtomicU64,
+        stall_timeout: Option<Duration>,
+        throttle: &std::sync::Mutex<Throttle>,
+    ) -> Result<(), AppError> {
+        let (start, end) = (segment.start, segment.end);
+        let mut position = offset.load(Ordering::Relaxed);
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(dest_path)
+            .await
+            .map_err(|e| err!("error.open_file", error = e))?;
+        file.seek(std::io::SeekFrom::Start(position))
+            .await
+            .map_err(|e| err!("error.write_file", error = e))?;
+
+        let mut resume_attempts = 0;
+        let mut stream = Self::send_request(url, position, Some(end), stall_timeout).await?.bytes_stream();
+
+        while position <= end {
+            while Self::is_paused(&self.operation_id) {
+                tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+            }
+
+            let error = match Self::with_timeout(stall_timeout, stream.next()).await? {
+                Some(Ok(chunk)) => {
+                    if chunk.len() as u64 > end - position + 1 {
+                        return Err(err!("error.range_overflow", start = start, end = end));
+                    }
+
+                    file.write_all(&chunk)
+                        .await
+                        .map_err(|e| err!("error.write_file", error = e))?;
+                    position += chunk.len() as u64;
+                    offset.store(position, Ordering::Relaxed);
+
+                    let delay = throttle.lock().unwrap().delay(chunk.len() as u64);
+                    if let Some(delay) = delay {
+                        tokio::time::sleep(delay).await;
+                    }
+                    continue;
+                }
+                Some(Err(e)) => e.to_string(),
+                None => t!("error.connection_closed_at", position = position),
+            };
+
+            if resume_attempts >= MAX_RESUME_ATTEMPTS {
+                return Err(err!("error.downloading_range", start = start, end = end, error = error));
+            }
+            resume_attempts += 1;
+            tracing::warn!(error = %error, start, end, position, attempt = resume_attempts, "Segment interrupted, resuming");
+            stream = Self::send_request(url, position, Some(end), stall_timeout).await?.bytes_stream();
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| err!("error.flush_file", error = e))
+    }
+
+    /// Trạng thái tải dở của file (nếu có)
+    pub fn load_partial(dest_path: &Path) -> Option<PartialDownload> {
+        std::fs::read_to_string(Self::partial_state_path(dest_path))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Phần tải dở của cùng nguồn và cùng phiên bản file, nguồn khác thì bỏ để tải lại từ đầu
+    fn resumable_partial(dest_path: &Path, url: &str, etag: Option<&str>, total_size: u64) -> Option<PartialDownload> {
+        let partial = Self::load_partial(dest_path)?;
+        if !partial.is_from(url, etag, total_size)
+            || !std::fs::metadata(dest_path).is_ok_and(|metadata| metadata.len() == total_size)
+        {
+            tracing::info!(url, previous = %partial.url, "Discarding stale partial download");
+            Self::remove_partial_state(dest_path);
+            return None;
+        }
+        Some(partial)
+    }
+
+    /// Xóa file tải dở và trạng thái của nó
+    pub fn discard_partial(dest_path: &Path) -> Result<(), AppError> {
+        Self::remove_partial_state(dest_path);
+        FileService::remove_path(dest_path)
+    }
+
+    fn save_partial(dest_path: &Path, partial: &PartialDownload) {
+        let result = serde_json::to_string(partial)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(Self::partial_state_path(dest_path), content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to save download state");
+        }
+    }
+
+    fn remove_partial_state(dest_path: &Path) {
+        let _ = std::fs::remove_file(Self::partial_state_path(dest_path));
+    }
+
+    fn partial_state_path(dest_path: &Path) -> PathBuf {
+        let mut path = dest_path.as_os_str().to_owned();
+        path.push(".part.json");
+        PathBuf::from(path)
+    }
+
+    /// Chờ trong lúc đang tạm dừng, trả về true nếu đã phải chờ
+    ///
+    /// Gọi progress callback khi bắt đầu và kết thúc tạm dừng để giao diện cập nhật trạng thái.
+    async fn wait_while_paused<F>(&self, downloaded: u64, total_size: u64, progress_callback: &mut F) -> bool
+    where
+        F: FnMut(u64, u64),
+    {
+        if !Self::is_paused(&self.operation_id) {
+            return false;
+        }
+
+        progress_callback(downloaded, total_size);
+        while Self::is_paused(&self.operation_id) {
+            tokio::time::sleep(PAUSE_POLL_INTERVAL).await;
+        }
+        progress_callback(downloaded, total_size);
+        true
+    }
+
+    /// Copy file từ nguồn release trên máy, trả về kích thước và sha256
+    async fn copy_local<F>(
+        &self,
+        source_path: &Path,
+        dest_path: &Path,
+        progress_callback: &mut F,
+    ) -> Result<(u64, String), AppError>
+    where
+        F: FnMut(u64, u64),
+    {
+        let mut source = File::open(source_path)
+            .await
+            .map_err(|e| err!("error.open_named", file = source_path.display(), error = e))?;
+        let total_size = source.metadata().await.map(|m| m.len()).unwrap_or(0);
+        let mut file = File::create(dest_path)
+            .await
+            .map_err(|e| err!("error.create_file", error = e))?;
+
+        let _active = ActiveDownload::start(&self.operation_id);
+        let mut buffer = vec![0u8; 1024 * 1024];
+        let mut copied: u64 = 0;
+        let mut hasher = Sha256::new();
+        let mut last_reported = Instant::now();
+
+        loop {
+            self.wait_while_paused(copied, total_size, progress_callback).await;
+
+            let read = source.read(&mut buffer)
+                .await
+                .map_err(|e| err!("error.read_named", file = source_path.display(), error = e))?;
+            if read == 0 {
+                break;
+            }
+
+            file.write_all(&buffer[..read])
+                .await
+                .map_err(|e| err!("error.write_file", error = e))?;
+            hasher.update(&buffer[..read]);
+
+            copied += read as u64;
+            if last_reported.elapsed() >= PROGRESS_INTERVAL {
+                progress_callback(copied, total_size);
+                last_reported = Instant::now();
+            }
+        }
+        progress_callback(copied, total_size);
+
+        file.flush()
+            .await
+            .map_err(|e| err!("error.flush_file", error = e))?;
+
+        Ok((copied, format!("{:x}", hasher.finalize())))
+    }
+
+    /// Đường dẫn trên máy nếu URL dạng file://
+    ///
+    /// Nguồn từ mạng (manifest, mirror, API) có thể trả về URL file:// trỏ tới file bất kỳ,
+    /// nên chỉ chấp nhận file nằm trong thư mục của nguồn release trên máy.
+    fn local_path(&self, url: &str) -> Result<Option<PathBuf>, AppError> {
+        if !url.starts_with("file://") {
+            return Ok(None);
+        }
+
+        let path = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .filter(|path| path.components().all(|c| c != std::path::Component::ParentDir))
+            .filter(|path| self.local_roots.iter().any(|root| path.starts_with(root)));
+
+        match path {
+            Some(path) => Ok(Some(path)),
+            None => {
+                tracing::warn!(url, "Rejected file URL outside local release source");
+                Err(err!("error.local_url_not_allowed", url = url))
+            }
+        }
+    }
+
+    async fn with_timeout<T>(timeout: Option<Duration>, future: impl Future<Output = T>) -> Result<T, AppError> {
+        match timeout {
+            Some(timeout) => tokio::time::timeout(timeout, future)
+                .await
+                .map_err(|_| err!("error.download_timeout", seconds = timeout.as_secs())),
+            None => Ok(future.await),
+        }
+    }
+
+    /// Thứ tự nguồn tải: nguồn nhanh nhất lần trước, các mirror đang bật theo thứ tự, GitHub.
+    /// Asset không có sha256 thì chỉ tải từ GitHub vì không kiểm tra được file từ mirror
+    fn download_sources(
+        asset: &ReleaseAsset,
+        mirrors: &[DownloadMirror],
+        stats: &BTreeMap<String, MirrorStat>,
+    ) -> Vec<(String, String, Duration)> {
+        let mirrors = if asset.sha256.is_some() { mirrors } else { &[] };
+        let mut sources: Vec<(String, String, Duration)> = mirrors.iter()
+            .filter(|mirror| mirror.enabled && mirror.name != CANONICAL_SOURCE)
+            .map(|mirror| (
+                mirror.name.clone(),
+                mirror.resolve_url(asset),
+                Duration::from_secs(mirror.timeout_secs.max(1)),
+            ))
+            .collect();
+        sources.push((CANONICAL_SOURCE.to_string(), asset.url.clone(), CANONICAL_TIMEOUT));
+
+        let fastest = sources.iter()
+            .enumerate()
+            .filter_map(|(index, (name, _, _))| {
+                stats.get(name)
+                    .filter(|stat| stat.bytes_per_second > 0)
+                    .map(|stat| (index, stat.bytes_per_second))
+            })
+            .max_by_key(|(_, bytes_per_second)| *bytes_per_second)
+            .map(|(index, _)| index);
+
+        if let Some(index) = fastest {
+            let source = sources.remove(index);
+            sources.insert(0, source);
+        }
+
+        sources
+    }
+
+    fn verify_asset(asset: &ReleaseAsset, size: u64, sha256: &str) -> Result<(), AppError> {
+        if asset.size > 0 && size != asset.size {
+            return Err(err!("error.size_mismatch", file = asset.name, expected = asset.size, actual = size));
+        }
+
+        if let Some(expected) = &asset.sha256 {
+            if !expected.eq_ignore_ascii_case(sha256) {
+                return Err(err!("error.hash_mismatch", file = asset.name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Thống kê tốc độ các nguồn tải, lỗi đọc thì coi như chưa có
+    pub fn load_mirror_stats() -> BTreeMap<String, MirrorStat> {
+        Self::get_mirror_stats_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_mirror_stats(stats: &BTreeMap<String, MirrorStat>) {
+        let Some(path) = Self::get_mirror_stats_path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+
+        let result = serde_json::to_string_pretty(stats)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to save mirror stats");
+        }
+    }
+
+    fn get_mirror_stats_path() -> Option<PathBuf> {
+        AppConfig::get_data_dir().ok().map(|dir| dir.join("mirror_stats.json"))
     }
 
     /// Download file đơn giản không có progress
-    pub async fn download_simple(&self, url: &str, dest_path: PathBuf) -> Result<PathBuf, String> {
+    pub async fn download_simple(&self, url: &str, dest_path: PathBuf) -> Result<PathBuf, AppError> {
         self.download_file(url, dest_path, |_, _| {}).await
     }
 
     /// Lấy kích thước file từ URL
-    pub async fn get_file_size(&self, url: &str) -> Result<u64, String> {
-        let response = self.client
+    pub async fn get_file_size(&self, url: &str) -> Result<u64, AppError> {
+        if let Some(path) = self.local_path(url)? {
+            return std::fs::metadata(&path)
+                .map(|metadata| metadata.len())
+                .map_err(|e| err!("error.file_metadata", error = e));
+        }
+
+        let response = HttpClientService::client()
             .head(url)
             .send()
             .await
-            .map_err(|e| format!("Failed to get file info: {}", e))?;
+            .map_err(|e| err!("error.file_metadata", error = e))?;
+
+        // Response của HEAD không có body nên phải đọc trực tiếp header Content-Length
+        Ok(response.headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0))
+    }
+
+    /// Tải một đoạn byte [start, end] của file, trả về None nếu server không hỗ trợ Range
+    ///
+    /// Dùng chung giới hạn tốc độ và tạm dừng với lượt tải thường, đoạn dài hơn `MAX_RANGE_SIZE` bị từ chối.
+    /// `progress_callback` nhận số byte đã tải và độ dài của đoạn.
+    pub async fn fetch_range<F>(
+        &self,
+        url: &str,
+        start: u64,
+        end: u64,
+        progress_callback: &mut F,
+    ) -> Result<Option<Vec<u8>>, AppError>
+    where
+        F: FnMut(u64, u64),
+    {
+        let length = end.checked_sub(start)
+            .and_then(|length| length.checked_add(1))
+            .filter(|length| *length <= Self::MAX_RANGE_SIZE)
+            .ok_or_else(|| err!("error.invalid_range", start = start, end = end, limit = Self::MAX_RANGE_SIZE))?;
+
+        let _active = ActiveDownload::start(&self.operation_id);
+        let mut throttle = Throttle::new();
+        let mut buffer = Vec::new();
+        progress_callback(0, length);
+
+        if let Some(path) = self.local_path(url)? {
+            let mut file = File::open(&path)
+                .await
+                .map_err(|e| err!("error.open_named", file = path.display(), error = e))?;
+            file.seek(std::io::SeekFrom::Start(start))
+                .await
+                .map_err(|e| err!("error.fetch_range", error = e))?;
 
-        Ok(response.content_length().unwrap_or(0))
+            let mut reader = file.take(length);
+            let mut chunk = vec![0u8; 64 * 1024];
+            loop {
+                let read = reader.read(&mut chunk)
+                    .await
+                    .map_err(|e| err!("error.fetch_range", error = e))?;
+                if read == 0 {
+                    break;
+                }
+                self.receive_range_chunk(&mut buffer, &chunk[..read], (start, end), &mut throttle, progress_callback)
+                    .await?;
+            }
+        } else {
+            let response = HttpClientService::client()
+                .get(url)
+                .header(reqwest::header::RANGE, format!("bytes={}-{}", start, end))
+                .send()
+                .await
+                .map_err(|e| err!("error.fetch_range", error = e))?;
+
+            if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                tracing::warn!(url, status = %response.status(), "Server does not support range requests");
+                return Ok(None);
+            }
+
+            let mut stream = response.bytes_stream();
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(|e| err!("error.downloading", error = e))?;
+                self.receive_range_chunk(&mut buffer, &chunk, (start, end), &mut throttle, progress_callback)
+                    .await?;
+            }
+        }
+
+        if buffer.len() as u64 != length {
+            return Err(err!("error.range_length", expected = length, actual = buffer.len()));
+        }
+
+        Ok(Some(buffer))
+    }
+
+    // Nhận thêm dữ liệu của đoạn [start, end]: không cho vượt độ dài, chờ khi tạm dừng và theo giới hạn tốc độ
+    async fn receive_range_chunk<F>(
+        &self,
+        buffer: &mut Vec<u8>,
+        chunk: &[u8],
+        (start, end): (u64, u64),
+        throttle: &mut Throttle,
+        progress_callback: &mut F,
+    ) -> Result<(), AppError>
+    where
+        F: FnMut(u64, u64),
+    {
+        let length = end - start + 1;
+        if buffer.len() as u64 + chunk.len() as u64 > length {
+            return Err(err!("error.range_overflow", start = start, end = end));
+        }
+
+        buffer.extend_from_slice(chunk);
+        progress_callback(buffer.len() as u64, length);
+
+        if self.wait_while_paused(buffer.len() as u64, length, progress_callback).await {
+            throttle.reset();
+        }
+        if let Some(delay) = throttle.delay(chunk.len() as u64) {
+            tokio::time::sleep(delay).await;
+        }
+        Ok(())
+    }
+
+    /// Tải toàn bộ nội dung file nhỏ (manifest, chữ ký) vào bộ nhớ
+    #[tracing::instrument(skip(self))]
+    pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>, AppError> {
+        if let Some(path) = self.local_path(url)? {
+            return tokio::fs::read(&path)
+                .await
+                .map_err(|e| err!("error.fetch_url", url = url, error = e));
+        }
+
+        let response = HttpClientService::client()
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| err!("error.fetch_url", url = url, error = e))?;
+
+        if !response.status().is_success() {
+            return Err(err!("error.download_status", status = response.status()));
+        }
+
+        response
+            .bytes()
+            .await
+            .map(|bytes| bytes.to_vec())
+            .map_err(|e| err!("error.fetch_url", url = url, error = e))
+    }
+
+    /// Tải và parse file JSON
+    pub async fn fetch_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, AppError> {
+        let content = self.fetch_bytes(url).await?;
+
+        serde_json::from_slice(&content)
+            .map_err(|e| err!("error.parse_url", url = url, error = e))
+    }
+}
+
+#[derive(Default)]
+struct DownloadOperation {
+    active: usize,
+    paused: bool,
+}
+
+// Đánh dấu một lượt tải đang chạy của thao tác, lượt cuối cùng kết thúc thì bỏ trạng thái tạm dừng
+struct ActiveDownload {
+    operation_id: String,
+}
+
+impl ActiveDownload {
+    fn start(operation_id: &str) -> Self {
+        OPERATIONS.lock().unwrap()
+            .entry(operation_id.to_string())
+            .or_default()
+            .active += 1;
+        Self { operation_id: operation_id.to_string() }
+    }
+}
+
+impl Drop for ActiveDownload {
+    fn drop(&mut self) {
+        let mut operations = OPERATIONS.lock().unwrap();
+        if let Some(operation) = operations.get_mut(&self.operation_id) {
+            operation.active -= 1;
+            if operation.active == 0 {
+                operations.remove(&self.operation_id);
+            }
+        }
+    }
+}
+
+// Giới hạn tốc độ theo số byte đã tải từ đầu cửa sổ thời gian hiện tại
+struct Throttle {
+    limit: u64,
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+impl Throttle {
+    // Bắt đầu cửa sổ mới định kỳ để khoảng mạng chậm không được bù bằng một đợt tải dồn
+    const WINDOW: Duration = Duration::from_secs(2);
+
+    fn new() -> Self {
+        Self {
+            limit: DownloadService::rate_limit(),
+            window_start: Instant::now(),
+            window_bytes: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+    }
+
+    /// Thời gian cần chờ sau khi nhận thêm `bytes` để không vượt giới hạn
+    ///
+    /// Không tự chờ để có thể dùng chung cho nhiều đoạn tải song song.
+    fn delay(&mut self, bytes: u64) -> Option<Duration> {
+        let limit = DownloadService::rate_limit();
+        if limit != self.limit || self.window_start.elapsed() >= Self::WINDOW {
+            self.limit = limit;
+            self.reset();
+        }
+        if limit == 0 {
+            return None;
+        }
+
+        self.window_bytes += bytes;
+        let expected = Duration::from_secs_f64(self.window_bytes as f64 / limit as f64);
+        expected.checked_sub(self.window_start.elapsed())
     }
 }
 
@@ -98,3 +986,161 @@ impl Default for DownloadService {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_url(path: &Path) -> String {
+        reqwest::Url::from_file_path(path).unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn reads_file_url_inside_local_root() {
+        let root = tempfile::tempdir().unwrap();
+        let asset = root.path().join("v1.0.0").join("pack.zip");
+        std::fs::create_dir_all(asset.parent().unwrap()).unwrap();
+        std::fs::write(&asset, b"0123456789").unwrap();
+
+        let service = DownloadService::with_local_roots(vec![root.path().to_path_buf()]);
+        let url = file_url(&asset);
+
+        assert_eq!(service.fetch_bytes(&url).await.unwrap(), b"0123456789");
+        assert_eq!(service.get_file_size(&url).await.unwrap(), 10);
+        assert_eq!(service.fetch_range(&url, 2, 4, &mut |_, _| {}).await.unwrap(), Some(b"234".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn rejects_file_url_without_local_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, b"secret").unwrap();
+
+        let service = DownloadService::new();
+        let url = file_url(&secret);
+
+        assert!(service.fetch_bytes(&url).await.is_err());
+        assert!(service.get_file_size(&url).await.is_err());
+        assert!(service.fetch_range(&url, 0, 1, &mut |_, _| {}).await.is_err());
+        assert!(service.download_simple(&url, dir.path().join("copy.txt")).await.is_err());
+        assert!(!dir.path().join("copy.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn rejects_file_url_outside_local_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("releases");
+        std::fs::create_dir_all(&root).unwrap();
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, b"secret").unwrap();
+
+        let service = DownloadService::with_local_roots(vec![root.clone()]);
+
+        assert!(service.fetch_bytes(&file_url(&secret)).await.is_err());
+        // Đường dẫn có `..` được chuẩn hóa khi parse URL, vẫn phải nằm ngoài thư mục nguồn
+        let escaped = format!("{}/../secret.txt", file_url(&root));
+        assert!(service.fetch_bytes(&escaped).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_or_oversized_ranges() {
+        let root = tempfile::tempdir().unwrap();
+        let asset = root.path().join("pack.zip");
+        std::fs::write(&asset, b"0123456789").unwrap();
+
+        let service = DownloadService::with_local_roots(vec![root.path().to_path_buf()]);
+        let url = file_url(&asset);
+
+        assert!(service.fetch_range(&url, 5, 4, &mut |_, _| {}).await.is_err());
+        assert!(service.fetch_range(&url, 0, u64::MAX, &mut |_, _| {}).await.is_err());
+        assert!(service.fetch_range(&url, 0, DownloadService::MAX_RANGE_SIZE, &mut |_, _| {}).await.is_err());
+        // Đoạn vượt quá cuối file
+        assert!(service.fetch_range(&url, 8, 20, &mut |_, _| {}).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn pause_applies_only_to_its_operation() {
+        let root = tempfile::tempdir().unwrap();
+        let asset = root.path().join("pack.zip");
+        std::fs::write(&asset, b"0123456789").unwrap();
+        let url = file_url(&asset);
+
+        let service = DownloadService::with_local_roots(vec![root.path().to_path_buf()]);
+        let paused = service.for_operation("test-paused");
+        let other = service.for_operation("test-other");
+
+        assert!(DownloadService::pause("test-paused").is_err());
+        let _active = ActiveDownload::start("test-paused");
+        DownloadService::pause("test-paused").unwrap();
+        assert!(!DownloadService::is_paused("test-other"));
+
+        // Thao tác khác vẫn tải được trong lúc thao tác kia tạm dừng
+        let bytes = tokio::time::timeout(Duration::from_secs(5), other.fetch_range(&url, 0, 3, &mut |_, _| {}))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(bytes, Some(b"0123".to_vec()));
+
+        let mut ignore_progress = |_, _| {};
+        let mut fetch = Box::pin(paused.fetch_range(&url, 0, 3, &mut ignore_progress));
+        assert!(tokio::time::timeout(Duration::from_millis(500), &mut fetch).await.is_err());
+
+        DownloadService::resume("test-paused");
+        let bytes = tokio::time::timeout(Duration::from_secs(5), fetch).await.unwrap().unwrap();
+        assert_eq!(bytes, Some(b"0123".to_vec()));
+    }
+
+    #[test]
+    fn resumes_partial_only_from_same_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("pack.zip");
+        let url = "https://mirror.example/pack.zip";
+        let etag = Some("\"v1\"");
+        std::fs::write(&dest, vec![0u8; 100]).unwrap();
+
+        let save = || DownloadService::save_partial(&dest, &PartialDownload::split(url, etag.map(str::to_string), 100, 2));
+
+        save();
+        assert!(DownloadService::resumable_partial(&dest, url, etag, 100).is_some());
+        assert!(DownloadService::resumable_partial(&dest, url, Some("\"v2\""), 100).is_none());
+        // Trạng thái không khớp đã bị xóa
+        assert!(DownloadService::load_partial(&dest).is_none());
+
+        save();
+        assert!(DownloadService::resumable_partial(&dest, "https://github.com/pack.zip", etag, 100).is_none());
+
+        save();
+        assert!(DownloadService::resumable_partial(&dest, url, etag, 200).is_none());
+    }
+
+    #[test]
+    fn skips_mirrors_without_canonical_hash() {
+        let mirror = DownloadMirror {
+            name: "mirror".to_string(),
+            url_template: "https://mirror.example/{repo}/{tag}/{asset}".to_string(),
+            enabled: true,
+            timeout_secs: 10,
+        };
+        let url = "https://github.com/owner/repo/releases/download/v1.0.0/pack.zip";
+        let stats = BTreeMap::new();
+
+        let unhashed = ReleaseAsset::new("owner/repo", "v1.0.0", "pack.zip", url, 10, None);
+        let sources = DownloadService::download_sources(&unhashed, std::slice::from_ref(&mirror), &stats);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].0, CANONICAL_SOURCE);
+
+        let hashed = ReleaseAsset::new("owner/repo", "v1.0.0", "pack.zip", url, 10, Some("ab".repeat(32)));
+        let sources = DownloadService::download_sources(&hashed, std::slice::from_ref(&mirror), &stats);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].0, "mirror");
+    }
+
+    #[test]
+    fn file_url_dir_only_for_file_urls() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("releases.json");
+
+        assert_eq!(DownloadService::file_url_dir(&file_url(&manifest)), Some(dir.path().to_path_buf()));
+        assert_eq!(DownloadService::file_url_dir("https://example.com/releases.json"), None);
+    }
+}
@@ -1,85 +1,33 @@
-use std::path::{Path, PathBuf};
+use crate::err;
+use crate::models::AppError;
+use std::path::{Component, Path, PathBuf};
 use std::fs;
-use zip::ZipArchive;
 
 pub struct FileService;
 
 impl FileService {
-    /// Giải nén file zip
-    pub fn extract_zip(zip_path: &Path, extract_to: &Path) -> Result<(), String> {
-        let file = fs::File::open(zip_path)
-            .map_err(|e| format!("Failed to open zip file: {}", e))?;
-
-        let mut archive = ZipArchive::new(file)
-            .map_err(|e| format!("Failed to read zip archive: {}", e))?;
-
-        // Tạo thư mục đích nếu chưa có
-        fs::create_dir_all(extract_to)
-            .map_err(|e| format!("Failed to create extraction directory: {}", e))?;
-
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i)
-                .map_err(|e| format!("Failed to read file from archive: {}", e))?;
-
-            let outpath = match file.enclosed_name() {
-                Some(path) => extract_to.join(path),
-                None => continue,
-            };
-
-            if file.name().ends_with('/') {
-                // Đây là thư mục
-                fs::create_dir_all(&outpath)
-                    .map_err(|e| format!("Failed to create directory: {}", e))?;
-            } else {
-                // Đây là file
-                if let Some(parent) = outpath.parent() {
-                    fs::create_dir_all(parent)
-                        .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-                }
-
-                let mut outfile = fs::File::create(&outpath)
-                    .map_err(|e| format!("Failed to create file: {}", e))?;
-
-                std::io::copy(&mut file, &mut outfile)
-                    .map_err(|e| format!("Failed to extract file: {}", e))?;
-            }
-
-            // Set permissions on Unix
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                if let Some(mode) = file.unix_mode() {
-                    fs::set_permissions(&outpath, fs::Permissions::from_mode(mode))
-                        .map_err(|e| format!("Failed to set permissions: {}", e))?;
-                }
-            }
-        }
-
-        Ok(())
-    }
-
     /// Xóa thư mục hoặc file
-    pub fn remove_path(path: &Path) -> Result<(), String> {
+    pub fn remove_path(path: &Path) -> Result<(), AppError> {
         if path.is_dir() {
             fs::remove_dir_all(path)
-                .map_err(|e| format!("Failed to remove directory: {}", e))
+                .map_err(|e| err!("error.remove_dir", error = e))
         } else if path.is_file() {
             fs::remove_file(path)
-                .map_err(|e| format!("Failed to remove file: {}", e))
+                .map_err(|e| err!("error.remove_file", error = e))
         } else {
             Ok(())
         }
     }
 
     /// Copy thư mục đệ quy
-    pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
+    pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), AppError> {
         fs::create_dir_all(dst)
-            .map_err(|e| format!("Failed to create destination directory: {}", e))?;
+            .map_err(|e| err!("error.create_dir", error = e))?;
 
         for entry in fs::read_dir(src)
-            .map_err(|e| format!("Failed to read source directory: {}", e))? 
+            .map_err(|e| err!("error.read_dir", error = e))? 
         {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
             let path = entry.path();
             let dest_path = dst.join(entry.file_name());
 
@@ -87,7 +35,7 @@ impl FileService {
                 Self::copy_dir_recursive(&path, &dest_path)?;
             } else {
                 fs::copy(&path, &dest_path)
-                    .map_err(|e| format!("Failed to copy file: {}", e))?;
+                    .map_err(|e| err!("error.copy_file", error = e))?;
             }
         }
 
@@ -95,9 +43,9 @@ impl FileService {
     }
 
     /// Tạo backup của thư mục
-    pub fn create_backup(source: &Path, backup_name: &str) -> Result<PathBuf, String> {
+    pub fn create_backup(source: &Path, backup_name: &str) -> Result<PathBuf, AppError> {
         let parent = source.parent()
-            .ok_or_else(|| "Source has no parent directory".to_string())?;
+            .ok_or_else(|| err!("error.no_parent_dir"))?;
 
         let backup_path = parent.join(format!("{}_backup", backup_name));
 
@@ -111,26 +59,26 @@ impl FileService {
     }
 
     /// Lấy kích thước thư mục
-    pub fn get_dir_size(path: &Path) -> Result<u64, String> {
+    pub fn get_dir_size(path: &Path) -> Result<u64, AppError> {
         let mut total_size = 0u64;
 
         if path.is_file() {
             return Ok(fs::metadata(path)
-                .map_err(|e| format!("Failed to get file metadata: {}", e))?
+                .map_err(|e| err!("error.file_metadata", error = e))?
                 .len());
         }
 
         for entry in fs::read_dir(path)
-            .map_err(|e| format!("Failed to read directory: {}", e))? 
+            .map_err(|e| err!("error.read_dir", error = e))? 
         {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
             let path = entry.path();
 
             if path.is_dir() {
                 total_size += Self::get_dir_size(&path)?;
             } else {
                 total_size += fs::metadata(&path)
-                    .map_err(|e| format!("Failed to get file metadata: {}", e))?
+                    .map_err(|e| err!("error.file_metadata", error = e))?
                     .len();
             }
         }
@@ -138,8 +86,75 @@ impl FileService {
         Ok(total_size)
     }
 
+    /// Kiểm tra đường dẫn tương đối không thoát ra ngoài thư mục gốc
+    pub fn safe_relative_path(relative: &str) -> Result<PathBuf, AppError> {
+        let path = PathBuf::from(relative);
+        let is_safe = !relative.is_empty()
+            && path.components().all(|c| matches!(c, Component::Normal(_)));
+
+        if is_safe {
+            Ok(path)
+        } else {
+            Err(err!("error.invalid_relative_path", path = relative))
+        }
+    }
+
+    /// Tính SHA-256 của file (dạng hex)
+    pub fn sha256_file(path: &Path) -> Result<String, AppError> {
+        use sha2::{Digest, Sha256};
+
+        let mut file = fs::File::open(path)
+            .map_err(|e| err!("error.open_file", error = e))?;
+        let mut hasher = Sha256::new();
+
+        std::io::copy(&mut file, &mut hasher)
+            .map_err(|e| err!("error.hash_file", error = e))?;
+
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Lấy dung lượng trống và tổng dung lượng của ổ đĩa chứa đường dẫn
+    pub fn get_disk_space(path: &Path) -> Result<(u64, u64), AppError> {
+        #[cfg(target_os = "windows")]
+        {
+            use std::ffi::OsStr;
+            use std::os::windows::ffi::OsStrExt;
+            use winapi::um::fileapi::GetDiskFreeSpaceExW;
+
+            let root = path.ancestors().last()
+                .ok_or_else(|| err!("error.root_path"))?;
+
+            let root_wide: Vec<u16> = OsStr::new(root)
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect();
+
+            let mut free_bytes: u64 = 0;
+            let mut total_bytes: u64 = 0;
+
+            unsafe {
+                if GetDiskFreeSpaceExW(
+                    root_wide.as_ptr(),
+                    &mut free_bytes as *mut u64 as *mut _,
+                    &mut total_bytes as *mut u64 as *mut _,
+                    std::ptr::null_mut(),
+                ) == 0 {
+                    return Err(err!("error.disk_space"));
+                }
+            }
+
+            Ok((free_bytes, total_bytes))
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            let _ = path;
+            Ok((0, 0))
+        }
+    }
+
     /// Kiểm tra đủ dung lượng đĩa không
-    pub fn check_disk_space(path: &Path, required_bytes: u64) -> Result<bool, String> {
+    pub fn check_disk_space(path: &Path, required_bytes: u64) -> Result<bool, AppError> {
         #[cfg(target_os = "windows")]
         {
             use std::ffi::OsStr;
@@ -147,7 +162,7 @@ impl FileService {
             use winapi::um::fileapi::GetDiskFreeSpaceExW;
 
             let root = path.ancestors().last()
-                .ok_or_else(|| "Failed to get root path".to_string())?;
+                .ok_or_else(|| err!("error.root_path"))?;
 
             let root_wide: Vec<u16> = OsStr::new(root)
                 .encode_wide()
@@ -163,7 +178,7 @@ impl FileService {
                     std::ptr::null_mut(),
                     &mut free_bytes as *mut u64 as *mut _,
                 ) == 0 {
-                    return Err("Failed to get disk space".to_string());
+                    return Err(err!("error.disk_space"));
                 }
             }
 
@@ -173,6 +188,7 @@ impl FileService {
         #[cfg(not(target_os = "windows"))]
         {
             // Fallback cho các hệ điều hành khác
+            let _ = (path, required_bytes);
             Ok(true)
         }
     }
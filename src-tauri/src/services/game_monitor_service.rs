@@ -0,0 +1,34 @@
+use crate::services::GameService;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Theo dõi tiến trình game (kể cả khi chạy qua DMM Game Player) và báo cho UI
+pub struct GameMonitorService;
+
+impl GameMonitorService {
+    pub fn start(app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut running = false;
+
+            loop {
+                let now_running = tauri::async_runtime::spawn_blocking(GameService::is_game_running)
+                    .await
+                    .unwrap_or(running);
+
+                if now_running != running {
+                    running = now_running;
+                    if running {
+                        tracing::info!("Game started");
+                    } else {
+                        tracing::info!("Game exited");
+                    }
+                    let _ = app.emit("game-status-changed", running);
+                }
+
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        });
+    }
+}
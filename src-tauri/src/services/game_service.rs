@@ -1,5 +1,10 @@
-use crate::models::GameInfo;
-use std::path::PathBuf;
+use crate::err;
+use crate::models::{AppError, GameInfo, LaunchMethod};
+use crate::services::DoorstopService;
+use std::path::{Path, PathBuf};
+
+const GAME_EXECUTABLE: &str = "PrincessConnectReDive.exe";
+const DMM_LAUNCH_URL: &str = "dmmgameplayer://play/GCL/priconner/cl/win";
 
 pub struct GameService;
 
@@ -20,29 +25,105 @@ impl GameService {
     }
 
     /// Validate game directory và trả về thông tin chi tiết
-    pub fn validate_game_path(path: PathBuf) -> Result<GameInfo, String> {
+    pub fn validate_game_path(path: PathBuf) -> Result<GameInfo, AppError> {
         let mut game_info = GameInfo::new(path.clone());
         
         if !game_info.validate() {
             let missing_files = game_info.get_missing_files();
             
             if missing_files.is_empty() {
-                return Err("Thư mục không hợp lệ. Không tìm thấy file game cần thiết.".to_string());
+                return Err(err!("error.game_dir_invalid"));
             } else {
                 let missing_list = missing_files.join(", ");
-                return Err(format!(
-                    "Thư mục không phải là game Princess Connect Re:Dive.\n\nThiếu các file: {}",
-                    missing_list
-                ));
+                return Err(err!("error.game_files_missing", files = missing_list));
             }
         }
 
         game_info.check_translation();
+        game_info.translation_enabled = game_info.has_translation && DoorstopService::is_enabled(&path);
         Ok(game_info)
     }
 
+    /// Kiểm tra game có đang chạy không
+    pub fn is_game_running() -> bool {
+        use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+        // Chỉ cần tên tiến trình, không lấy CPU/bộ nhớ để việc kiểm tra định kỳ nhẹ hơn
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+
+        system.processes()
+            .values()
+            .any(|process| process.name().eq_ignore_ascii_case(GAME_EXECUTABLE))
+    }
+
+    /// Báo lỗi nếu game đang chạy (không thể ghi đè file khi game đang mở)
+    pub fn ensure_game_not_running() -> Result<(), AppError> {
+        if Self::is_game_running() {
+            Err(err!("error.game_running_close"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Chạy game qua DMM Game Player hoặc trực tiếp từ file exe
+    pub fn launch_game(game_path: &Path, method: LaunchMethod, args: &str) -> Result<(), AppError> {
+        if Self::is_game_running() {
+            return Err(err!("error.game_running"));
+        }
+
+        match method {
+            LaunchMethod::Dmm => Self::open_url(DMM_LAUNCH_URL),
+            LaunchMethod::Direct => {
+                let executable = game_path.join(GAME_EXECUTABLE);
+                if !executable.exists() {
+                    return Err(err!("error.game_executable_not_found", path = executable.display()));
+                }
+
+                std::process::Command::new(&executable)
+                    .args(Self::split_args(args))
+                    .current_dir(game_path)
+                    .spawn()
+                    .map_err(|e| err!("error.launch_game", error = e))?;
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Mở URL bằng trình xử lý protocol mặc định của hệ điều hành
+    fn open_url(url: &str) -> Result<(), AppError> {
+        tauri_plugin_opener::open_url(url, None::<&str>)
+            .map_err(|e| err!("error.open_dmm", error = e))
+    }
+
+    /// Tách tham số theo khoảng trắng, giữ nguyên phần trong dấu ngoặc kép
+    fn split_args(args: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut current = String::new();
+        let mut in_quotes = false;
+
+        for c in args.chars() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                c if c.is_whitespace() && !in_quotes => {
+                    if !current.is_empty() {
+                        result.push(std::mem::take(&mut current));
+                    }
+                }
+                c => current.push(c),
+            }
+        }
+
+        if !current.is_empty() {
+            result.push(current);
+        }
+
+        result
+    }
+
     /// Lấy thông tin phiên bản game
-    pub fn get_game_version(game_path: &PathBuf) -> Option<String> {
+    pub fn get_game_version(game_path: &Path) -> Option<String> {
         // Đọc version từ file version.txt hoặc assembly info
         let version_file = game_path.join("version.txt");
         if version_file.exists() {
@@ -0,0 +1,37 @@
+use crate::models::{AppError, Release};
+use crate::services::{DownloadService, ReleaseProvider};
+use async_trait::async_trait;
+
+/// Release trên Gitea/Forgejo tự host (API tương thích định dạng của GitHub)
+pub struct GiteaService {
+    download_service: DownloadService,
+    base_url: String,
+    repo: String,
+}
+
+impl GiteaService {
+    pub fn new(base_url: String, repo: String) -> Self {
+        Self {
+            download_service: DownloadService::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            repo,
+        }
+    }
+}
+
+#[async_trait]
+impl ReleaseProvider for GiteaService {
+    fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    async fn get_latest_release(&self) -> Result<Release, AppError> {
+        let url = format!("{}/api/v1/repos/{}/releases/latest", self.base_url, self.repo);
+        self.download_service.fetch_json(&url).await
+    }
+
+    async fn get_all_releases(&self) -> Result<Vec<Release>, AppError> {
+        let url = format!("{}/api/v1/repos/{}/releases", self.base_url, self.repo);
+        self.download_service.fetch_json(&url).await
+    }
+}
@@ -0,0 +1,158 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, HistoryEntry, HistoryOperation, HistoryOutcome};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Giới hạn số mục để file lịch sử không phình mãi
+const MAX_ENTRIES: usize = 1000;
+
+// Các thao tác có thể ghi lịch sử cùng lúc (vd: backup trong lúc cài)
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Thông tin một thao tác đang chạy, dùng để ghi lịch sử khi kết thúc
+pub struct HistoryRecorder {
+    operation: HistoryOperation,
+    profile: String,
+    from_version: Option<String>,
+    to_version: Option<String>,
+    started_at: DateTime<Utc>,
+}
+
+impl HistoryRecorder {
+    pub fn start(
+        operation: HistoryOperation,
+        game_path: &Path,
+        from_version: Option<String>,
+        to_version: Option<String>,
+    ) -> Self {
+        Self {
+            operation,
+            profile: game_path.to_string_lossy().to_string(),
+            from_version,
+            to_version,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Ghi kết quả thao tác, lỗi khi ghi lịch sử chỉ được log lại
+    pub fn finish<T>(self, result: &Result<T, AppError>, bytes_downloaded: u64) {
+        let duration_ms = (Utc::now() - self.started_at).num_milliseconds().max(0) as u64;
+        let (outcome, error_code, error_message) = match result {
+            Ok(_) => (HistoryOutcome::Success, None, None),
+            Err(e) => (
+                HistoryOutcome::Failed,
+                Some(e.code().to_string()),
+                Some(e.to_string()),
+            ),
+        };
+
+        let entry = HistoryEntry {
+            operation: self.operation,
+            profile: self.profile,
+            from_version: self.from_version,
+            to_version: self.to_version,
+            started_at: self.started_at.to_rfc3339(),
+            duration_ms,
+            bytes_downloaded,
+            outcome,
+            error_code,
+            error_message,
+        };
+
+        if let Err(e) = HistoryService::append(entry) {
+            tracing::warn!(error = %e, "Failed to record history");
+        }
+    }
+}
+
+pub struct HistoryService;
+
+impl HistoryService {
+    /// Đọc lịch sử, mục mới nhất ở cuối
+    pub fn load() -> Result<Vec<HistoryEntry>, AppError> {
+        let history_path = Self::get_history_path()?;
+        if !history_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&history_path)
+            .map_err(|e| err!("error.read_history", error = e))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| err!("error.parse_history", error = e))
+    }
+
+    pub fn append(entry: HistoryEntry) -> Result<(), AppError> {
+        let _lock = HISTORY_LOCK.lock().unwrap();
+
+        let mut entries = Self::load()?;
+        entries.push(entry);
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+        }
+
+        Self::save(&entries)
+    }
+
+    pub fn clear() -> Result<(), AppError> {
+        let _lock = HISTORY_LOCK.lock().unwrap();
+
+        let history_path = Self::get_history_path()?;
+        if history_path.exists() {
+            fs::remove_file(&history_path)
+                .map_err(|e| err!("error.clear_history", error = e))?;
+        }
+
+        Ok(())
+    }
+
+    fn save(entries: &[HistoryEntry]) -> Result<(), AppError> {
+        let history_path = Self::get_history_path()?;
+
+        if let Some(parent) = history_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| err!("error.create_config_dir", error = e))?;
+        }
+
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| err!("error.serialize_history", error = e))?;
+
+        fs::write(&history_path, content)
+            .map_err(|e| err!("error.write_history", error = e))
+    }
+
+    fn get_history_path() -> Result<PathBuf, AppError> {
+        Ok(AppConfig::get_data_dir()?.join("history.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_code_comes_from_catalogue_key() {
+        let signature = err!("error.pack_not_signed");
+        let integrity = err!("error.hash_mismatch", file = "pack.zip");
+        let wrapped = err!("error.download_all_sources", errors = format!("github: {}", integrity))
+            .caused_by(&integrity);
+        let game_running = err!("error.game_running");
+
+        assert_eq!(signature.code(), "signature");
+        assert_eq!(integrity.code(), "integrity");
+        assert_eq!(wrapped.code(), "integrity");
+        assert_eq!(game_running.code(), "game_running");
+        assert_eq!(err!("error.download_all_sources", errors = "github: timeout").code(), "network");
+    }
+
+    #[test]
+    fn error_code_uses_os_error_number() {
+        let error = std::io::Error::from_raw_os_error(if cfg!(windows) { 112 } else { 28 });
+        assert_eq!(err!("error.write_file", error = error).code(), "disk_full");
+
+        // Thông báo không tạo từ danh mục thì không đoán theo nội dung
+        assert_eq!(AppError::from("download failed: signature mismatch".to_string()).code(), "unknown");
+    }
+}
@@ -0,0 +1,118 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, NetworkConfig, ProxyConfig};
+use reqwest::{Certificate, Client, NoProxy, Proxy, Url};
+use std::path::Path;
+use std::sync::RwLock;
+use std::time::Duration;
+
+pub const DEFAULT_USER_AGENT: &str = "PriconneVH-Installer/1.0";
+
+// Client dùng chung, tạo lại khi thay đổi cài đặt mạng
+static CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+
+pub struct HttpClientService;
+
+impl HttpClientService {
+    /// HTTP client dùng chung cho mọi service, tạo từ cài đặt mạng ở lần gọi đầu tiên
+    pub fn client() -> Client {
+        if let Some(client) = CLIENT.read().unwrap().as_ref() {
+            return client.clone();
+        }
+
+        let network = AppConfig::load()
+            .map(|config| config.network)
+            .unwrap_or_default();
+        let client = Self::build(&network).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Invalid network settings, using defaults");
+            Self::build(&NetworkConfig::default()).unwrap_or_default()
+        });
+
+        *CLIENT.write().unwrap() = Some(client.clone());
+        client
+    }
+
+    /// Áp dụng cài đặt mạng mới cho các request tiếp theo
+    pub fn reload(network: &NetworkConfig) -> Result<(), AppError> {
+        let client = Self::build(network)?;
+        *CLIENT.write().unwrap() = Some(client);
+        tracing::info!(proxy = ?Self::proxy_mode(&network.proxy), "Reloaded HTTP client");
+        Ok(())
+    }
+
+    /// Tạo client theo cài đặt mạng, báo lỗi nếu cài đặt không hợp lệ
+    pub fn build(network: &NetworkConfig) -> Result<Client, AppError> {
+        let user_agent = match network.user_agent.trim() {
+            "" => DEFAULT_USER_AGENT,
+            user_agent => user_agent,
+        };
+
+        let mut builder = Client::builder()
+            .user_agent(user_agent)
+            .connect_timeout(Duration::from_secs(network.connect_timeout_secs.max(1)));
+
+        if network.read_timeout_secs > 0 {
+            builder = builder.read_timeout(Duration::from_secs(network.read_timeout_secs));
+        }
+
+        builder = match &network.proxy {
+            ProxyConfig::Direct => builder.no_proxy(),
+            // reqwest tự đọc biến môi trường và cài đặt proxy của hệ điều hành
+            ProxyConfig::System => builder,
+            ProxyConfig::Custom { url, username, password, no_proxy } => {
+                builder.proxy(Self::custom_proxy(url, username.as_deref(), password.as_deref(), no_proxy)?)
+            }
+        };
+
+        for path in &network.extra_ca_certs {
+            for certificate in Self::load_certificates(path)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        builder.build()
+            .map_err(|e| err!("error.create_http_client", error = e))
+    }
+
+    fn custom_proxy(url: &str, username: Option<&str>, password: Option<&str>, no_proxy: &str) -> Result<Proxy, AppError> {
+        let mut proxy_url = Url::parse(url.trim())
+            .map_err(|e| err!("error.invalid_proxy_url", error = e))?;
+
+        if !matches!(proxy_url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+            return Err(err!("error.unsupported_proxy_scheme", scheme = proxy_url.scheme()));
+        }
+
+        // Đưa thông tin đăng nhập vào URL để dùng được cho cả proxy HTTP và SOCKS5
+        if let Some(username) = username.filter(|username| !username.is_empty()) {
+            proxy_url.set_username(username)
+                .map_err(|_| err!("error.invalid_proxy_username"))?;
+            proxy_url.set_password(password)
+                .map_err(|_| err!("error.invalid_proxy_password"))?;
+        }
+
+        let proxy = Proxy::all(proxy_url.as_str())
+            .map_err(|e| err!("error.invalid_proxy_url", error = e))?;
+        Ok(proxy.no_proxy(NoProxy::from_string(no_proxy)))
+    }
+
+    /// Đọc chứng chỉ CA, file PEM có thể chứa nhiều chứng chỉ
+    fn load_certificates(path: &Path) -> Result<Vec<Certificate>, AppError> {
+        let content = std::fs::read(path)
+            .map_err(|e| err!("error.read_ca_certificate", path = path.display(), error = e))?;
+
+        let certificates = if content.windows(10).any(|window| window == b"-----BEGIN") {
+            Certificate::from_pem_bundle(&content)
+        } else {
+            Certificate::from_der(&content).map(|certificate| vec![certificate])
+        };
+
+        certificates.map_err(|e| err!("error.invalid_ca_certificate", path = path.display(), error = e))
+    }
+
+    fn proxy_mode(proxy: &ProxyConfig) -> &'static str {
+        match proxy {
+            ProxyConfig::Direct => "direct",
+            ProxyConfig::System => "system",
+            ProxyConfig::Custom { .. } => "custom",
+        }
+    }
+}
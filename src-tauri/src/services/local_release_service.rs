@@ -0,0 +1,141 @@
+use crate::err;
+use crate::models::{AppError, Release, ReleaseFile};
+use crate::services::ReleaseProvider;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RELEASE_INFO_FILE: &str = "release.json";
+
+/// Thông tin tùy chọn trong release.json của mỗi thư mục release
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LocalReleaseInfo {
+    name: Option<String>,
+    published_at: Option<String>,
+    body: String,
+}
+
+/// Release từ thư mục trên máy: mỗi thư mục con là một release đặt tên theo tag,
+/// các file trong đó là asset (release.json tùy chọn chứa name, published_at, body)
+pub struct LocalReleaseService {
+    path: PathBuf,
+    repo: String,
+}
+
+impl LocalReleaseService {
+    pub fn new(path: PathBuf, repo: String) -> Self {
+        Self { path, repo }
+    }
+
+    fn read_release(dir: &Path) -> Result<Release, AppError> {
+        let tag_name = dir.file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let info: LocalReleaseInfo = match fs::read_to_string(dir.join(RELEASE_INFO_FILE)) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| {
+                    err!("error.parse_release_info", file = RELEASE_INFO_FILE, release = tag_name, error = e)
+                })?,
+            Err(_) => LocalReleaseInfo::default(),
+        };
+
+        let mut assets = Vec::new();
+        for entry in fs::read_dir(dir).map_err(|e| err!("error.read_release_dir", error = e))? {
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !path.is_file() || name == RELEASE_INFO_FILE {
+                continue;
+            }
+
+            let metadata = entry.metadata()
+                .map_err(|e| err!("error.file_metadata", error = e))?;
+            let browser_download_url = reqwest::Url::from_file_path(&path)
+                .map_err(|_| err!("error.invalid_asset_path", path = path.display()))?
+                .to_string();
+
+            assets.push(ReleaseFile {
+                name,
+                size: metadata.len(),
+                browser_download_url,
+                download_count: 0,
+                digest: None,
+            });
+        }
+        assets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Không có ngày phát hành thì lấy thời gian sửa thư mục
+        let published_at = info.published_at.unwrap_or_else(|| {
+            fs::metadata(dir)
+                .and_then(|metadata| metadata.modified())
+                .map(|modified| chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339())
+                .unwrap_or_default()
+        });
+
+        Ok(Release {
+            name: info.name.unwrap_or_else(|| tag_name.clone()),
+            tag_name,
+            published_at,
+            body: info.body,
+            assets,
+        })
+    }
+}
+
+#[async_trait]
+impl ReleaseProvider for LocalReleaseService {
+    fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    fn local_root(&self) -> Option<PathBuf> {
+        Some(self.path.clone())
+    }
+
+    async fn get_all_releases(&self) -> Result<Vec<Release>, AppError> {
+        let mut releases = Vec::new();
+
+        for entry in fs::read_dir(&self.path)
+            .map_err(|e| err!("error.read_releases_dir", path = self.path.display(), error = e))?
+        {
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
+            if entry.path().is_dir() {
+                releases.push(Self::read_release(&entry.path())?);
+            }
+        }
+
+        // Mới nhất trước giống API của GitHub
+        releases.sort_by(|a, b| b.published_at.cmp(&a.published_at));
+        Ok(releases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::DownloadService;
+
+    #[tokio::test]
+    async fn local_directory_stand_in_serves_releases() {
+        let dir = tempfile::tempdir().unwrap();
+        for (tag, published_at) in [("v1.0.0", "2024-01-01T00:00:00Z"), ("v1.1.0", "2024-02-01T00:00:00Z")] {
+            let release_dir = dir.path().join(tag);
+            fs::create_dir_all(&release_dir).unwrap();
+            fs::write(release_dir.join("pack.zip"), tag).unwrap();
+            fs::write(release_dir.join(RELEASE_INFO_FILE), format!(r#"{{"published_at": "{}"}}"#, published_at))
+                .unwrap();
+        }
+
+        let provider = LocalReleaseService::new(dir.path().to_path_buf(), "owner/repo".to_string());
+        let latest = provider.get_latest_release().await.unwrap();
+        assert_eq!(latest.tag_name, "v1.1.0");
+        assert_eq!(latest.assets.len(), 1);
+
+        let download_service = DownloadService::with_local_roots(provider.local_root().into_iter().collect());
+        let content = download_service.fetch_bytes(&latest.assets[0].browser_download_url).await.unwrap();
+        assert_eq!(content, b"v1.1.0");
+    }
+}
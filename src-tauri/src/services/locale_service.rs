@@ -0,0 +1,179 @@
+use crate::models::AppConfig;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+
+// Danh mục thông báo nhúng vào lúc build, key dạng `nhóm.tên`, tham số dạng `{tên}`
+const VI_CATALOGUE: &str = include_str!("../../locales/vi.json");
+const EN_CATALOGUE: &str = include_str!("../../locales/en.json");
+
+static CURRENT_LOCALE: RwLock<Locale> = RwLock::new(Locale::DEFAULT);
+
+/// Lấy thông báo theo ngôn ngữ hiện tại, tham số truyền dạng `tên = giá trị`
+///
+/// `t!("error.read_config", error = e)`
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::services::LocaleService::text($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::services::LocaleService::text(
+            $key,
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+
+/// Tạo `AppError` từ thông báo trong danh mục, giữ key để phân loại lỗi
+///
+/// `Err(err!("error.read_config", error = e))`
+#[macro_export]
+macro_rules! err {
+    ($key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::models::AppError::new($key, $crate::t!($key $(, $name = $value)*))
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Vi,
+    En,
+}
+
+impl Locale {
+    pub const DEFAULT: Locale = Locale::Vi;
+    // Ngôn ngữ dùng khi danh mục hiện tại thiếu key, bản tiếng Anh luôn đầy đủ nhất
+    const FALLBACK: Locale = Locale::En;
+
+    /// Nhận cả mã có vùng như `en-US`, `vi_VN`
+    pub fn from_code(code: &str) -> Option<Self> {
+        let language = code.trim().split(['-', '_']).next().unwrap_or_default();
+
+        match language.to_lowercase().as_str() {
+            "vi" => Some(Locale::Vi),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::Vi => "vi",
+            Locale::En => "en",
+        }
+    }
+
+    fn catalogue(&self) -> &'static HashMap<String, String> {
+        static VI: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+        match self {
+            Locale::Vi => VI.get_or_init(|| Self::parse(VI_CATALOGUE, "vi")),
+            Locale::En => EN.get_or_init(|| Self::parse(EN_CATALOGUE, "en")),
+        }
+    }
+
+    fn parse(content: &str, code: &str) -> HashMap<String, String> {
+        serde_json::from_str(content).unwrap_or_else(|e| {
+            tracing::error!(locale = code, error = %e, "Failed to parse message catalogue");
+            HashMap::new()
+        })
+    }
+}
+
+/// Thông báo hiển thị cho người dùng (lỗi, hộp thoại, khay, thông báo hệ thống) theo `AppConfig.language`
+pub struct LocaleService;
+
+impl LocaleService {
+    /// Gọi lúc khởi động, trước khi có thông báo nào được tạo
+    pub fn init() {
+        let language = AppConfig::load()
+            .map(|config| config.language)
+            .unwrap_or_default();
+        Self::set_language(&language);
+    }
+
+    /// Đổi ngôn ngữ lúc đang chạy, mã không hỗ trợ thì dùng ngôn ngữ mặc định
+    pub fn set_language(code: &str) -> Locale {
+        let locale = Locale::from_code(code).unwrap_or_else(|| {
+            tracing::warn!(language = code, "Unsupported language, using default");
+            Locale::DEFAULT
+        });
+
+        *CURRENT_LOCALE.write().unwrap() = locale;
+        locale
+    }
+
+    pub fn current() -> Locale {
+        *CURRENT_LOCALE.read().unwrap()
+    }
+
+    /// Tìm key ở ngôn ngữ hiện tại, rồi tiếng Anh, không có thì trả về chính key
+    pub fn text(key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let template = Self::current().catalogue().get(key)
+            .or_else(|| Locale::FALLBACK.catalogue().get(key));
+
+        let Some(template) = template else {
+            tracing::warn!(key, "Missing message in catalogue");
+            return key.to_string();
+        };
+
+        Self::format(template, args)
+    }
+
+    // Thay `{tên}` bằng giá trị trong một lượt, giá trị có chứa `{...}` cũng không bị thay tiếp
+    fn format(template: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut text = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find('{') {
+            text.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+
+            let placeholder = after.find('}').and_then(|end| {
+                args.iter()
+                    .find(|(name, _)| *name == &after[..end])
+                    .map(|(_, value)| (value, end))
+            });
+            match placeholder {
+                Some((value, end)) => {
+                    text.push_str(&value.to_string());
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = after;
+                }
+            }
+        }
+
+        text.push_str(rest);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_placeholders_in_one_pass() {
+        let text = LocaleService::format("{file}: {error}", &[("file", &"{error}"), ("error", &"disk full")]);
+        assert_eq!(text, "{error}: disk full");
+        assert_eq!(LocaleService::format("{missing}", &[]), "{missing}");
+    }
+
+    #[test]
+    fn every_key_exists_in_both_catalogues() {
+        let vi = Locale::Vi.catalogue();
+        let en = Locale::En.catalogue();
+        assert!(!en.is_empty());
+
+        let mut missing: Vec<_> = en.keys().filter(|key| !vi.contains_key(*key))
+            .chain(vi.keys().filter(|key| !en.contains_key(*key)))
+            .collect();
+        missing.sort();
+        assert!(missing.is_empty(), "missing keys: {:?}", missing);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, LogEntry};
+use std::fs;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+const LOG_FILE_PREFIX: &str = "priconevh";
+const MAX_LOG_FILES: usize = 7;
+const DEFAULT_LIMIT: usize = 500;
+
+// Giữ writer chạy nền đến khi app thoát và handle để đổi level lúc đang chạy
+static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+pub struct LoggingService;
+
+impl LoggingService {
+    /// Khởi tạo log ra file JSON xoay vòng theo ngày trong thư mục log của app
+    pub fn init(level: &str) -> Result<(), AppError> {
+        let logs_dir = AppConfig::get_logs_dir()?;
+        fs::create_dir_all(&logs_dir)
+            .map_err(|e| err!("error.create_logs_dir", error = e))?;
+
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(&logs_dir)
+            .map_err(|e| err!("error.create_log_file", error = e))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        let (filter, handle) = reload::Layer::new(Self::build_filter(level)?);
+
+        let file_layer = fmt::layer()
+            .json()
+            .with_current_span(false)
+            .with_span_list(true)
+            .with_writer(writer);
+
+        // Khi dev thì in thêm ra console (stderr để không lẫn với output của CLI)
+        let console_layer = cfg!(debug_assertions).then(|| fmt::layer().with_writer(std::io::stderr));
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(file_layer)
+            .with(console_layer)
+            .try_init()
+            .map_err(|e| err!("error.init_logging", error = e))?;
+
+        let _ = LOG_GUARD.set(guard);
+        let _ = FILTER_HANDLE.set(handle);
+        Ok(())
+    }
+
+    /// Log ra stderr khi không ghi được file log, level không hợp lệ thì dùng info
+    pub fn init_stderr(level: &str) {
+        let filter = Self::build_filter(level)
+            .unwrap_or_else(|_| EnvFilter::new("warn,priconevh_lib=info"));
+        let (filter, handle) = reload::Layer::new(filter);
+
+        // Đã có subscriber khác thì giữ nguyên
+        let initialized = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_writer(std::io::stderr))
+            .try_init()
+            .is_ok();
+        if initialized {
+            let _ = FILTER_HANDLE.set(handle);
+        }
+    }
+
+    /// Đổi level log lúc đang chạy
+    pub fn set_level(level: &str) -> Result<(), AppError> {
+        let filter = Self::build_filter(level)?;
+
+        if let Some(handle) = FILTER_HANDLE.get() {
+            handle.reload(filter)
+                .map_err(|e| err!("error.change_log_level", error = e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Level áp dụng cho log của app, thư viện bên ngoài chỉ ghi từ warn trở lên
+    fn build_filter(level: &str) -> Result<EnvFilter, AppError> {
+        let level = Level::from_str(level)
+            .map_err(|_| err!("error.invalid_log_level", level = level))?;
+
+        EnvFilter::try_new(format!("warn,priconevh_lib={}", level))
+            .map_err(|e| err!("error.invalid_log_level", level = e))
+    }
+
+    /// Đọc các dòng log gần nhất (cũ trước, mới sau)
+    ///
+    /// `min_level`: chỉ lấy log từ level này trở lên, `query`: lọc theo nội dung (không phân biệt hoa thường)
+    pub fn read_logs(
+        min_level: Option<&str>,
+        query: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Vec<LogEntry>, AppError> {
+        let min_level = match min_level {
+            Some(level) => Some(
+                Level::from_str(level).map_err(|_| err!("error.invalid_log_level", level = level))?
+            ),
+            None => None,
+        };
+        let query = query
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty());
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+        let logs_dir = AppConfig::get_logs_dir()?;
+        let Ok(entries) = fs::read_dir(&logs_dir) else {
+            return Ok(Vec::new());
+        };
+
+        // Tên file có ngày (priconevh.YYYY-MM-DD.log) nên sắp xếp theo tên là theo thời gian
+        let mut files: Vec<_> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().starts_with(LOG_FILE_PREFIX))
+                    .unwrap_or(false)
+            })
+            .collect();
+        files.sort();
+
+        let mut logs = Vec::new();
+        for path in files {
+            let content = match fs::read(&path) {
+                Ok(content) => String::from_utf8_lossy(&content).to_string(),
+                Err(_) => continue,
+            };
+
+            for line in content.lines() {
+                let Some(entry) = Self::parse_line(line) else {
+                    continue;
+                };
+
+                if let Some(min_level) = min_level {
+                    // Trong tracing, level càng chi tiết thì càng lớn (TRACE > ERROR)
+                    match Level::from_str(&entry.level) {
+                        Ok(level) if level <= min_level => {}
+                        _ => continue,
+                    }
+                }
+
+                if let Some(query) = &query {
+                    let matches = entry.message.to_lowercase().contains(query)
+                        || entry.target.to_lowercase().contains(query)
+                        || serde_json::Value::Object(entry.fields.clone())
+                            .to_string()
+                            .to_lowercase()
+                            .contains(query);
+                    if !matches {
+                        continue;
+                    }
+                }
+
+                logs.push(entry);
+            }
+        }
+
+        if logs.len() > limit {
+            logs.drain(..logs.len() - limit);
+        }
+
+        Ok(logs)
+    }
+
+    fn parse_line(line: &str) -> Option<LogEntry> {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+
+        let mut fields = value.get("fields")
+            .and_then(|f| f.as_object())
+            .cloned()
+            .unwrap_or_default();
+        let message = fields.remove("message")
+            .and_then(|m| m.as_str().map(String::from))
+            .unwrap_or_default();
+
+        let spans = value.get("spans")
+            .and_then(|s| s.as_array())
+            .map(|spans| {
+                spans.iter()
+                    .filter_map(|span| span.get("name").and_then(|n| n.as_str()))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(LogEntry {
+            timestamp: value.get("timestamp")?.as_str()?.to_string(),
+            level: value.get("level")?.as_str()?.to_string(),
+            target: value.get("target").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+            message,
+            fields,
+            spans,
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use crate::models::{AppError, Release};
+use crate::services::{DownloadService, ReleaseProvider};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// Danh sách release từ một file JSON tĩnh (http(s) hoặc file://), mới nhất trước
+pub struct ManifestReleaseService {
+    download_service: DownloadService,
+    url: String,
+    repo: String,
+}
+
+impl ManifestReleaseService {
+    pub fn new(url: String, repo: String) -> Self {
+        Self {
+            download_service: DownloadService::with_local_roots(DownloadService::file_url_dir(&url).into_iter().collect()),
+            url,
+            repo,
+        }
+    }
+}
+
+#[async_trait]
+impl ReleaseProvider for ManifestReleaseService {
+    fn repo(&self) -> &str {
+        &self.repo
+    }
+
+    // Manifest là file trên máy thì asset cũng được phép nằm cạnh nó
+    fn local_root(&self) -> Option<PathBuf> {
+        DownloadService::file_url_dir(&self.url)
+    }
+
+    async fn get_all_releases(&self) -> Result<Vec<Release>, AppError> {
+        self.download_service.fetch_json(&self.url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn file_url(path: &Path) -> String {
+        reqwest::Url::from_file_path(path).unwrap().to_string()
+    }
+
+    // Manifest trên máy thay cho GitHub: asset nằm cạnh manifest, một asset trỏ ra ngoài thư mục
+    fn write_stand_in(dir: &Path, outside: &Path) -> String {
+        std::fs::write(dir.join("pack.zip"), b"pack").unwrap();
+        let releases = serde_json::json!([{
+            "name": "v1.0.0",
+            "tag_name": "v1.0.0",
+            "published_at": "2024-01-01T00:00:00Z",
+            "body": "",
+            "assets": [
+                {
+                    "name": "pack.zip",
+                    "size": 4,
+                    "browser_download_url": file_url(&dir.join("pack.zip")),
+                    "download_count": 0
+                },
+                {
+                    "name": "outside.zip",
+                    "size": 6,
+                    "browser_download_url": file_url(outside),
+                    "download_count": 0
+                }
+            ]
+        }]);
+        let manifest = dir.join("releases.json");
+        std::fs::write(&manifest, releases.to_string()).unwrap();
+        file_url(&manifest)
+    }
+
+    #[tokio::test]
+    async fn local_manifest_lists_releases_and_allows_its_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source");
+        std::fs::create_dir_all(&source).unwrap();
+        let outside = dir.path().join("outside.zip");
+        std::fs::write(&outside, b"secret").unwrap();
+        let url = write_stand_in(&source, &outside);
+
+        let provider = ManifestReleaseService::new(url, "owner/repo".to_string());
+        assert_eq!(provider.local_root(), Some(source.clone()));
+
+        let release = provider.get_latest_release().await.unwrap();
+        assert_eq!(release.tag_name, "v1.0.0");
+
+        let download_service = DownloadService::with_local_roots(provider.local_root().into_iter().collect());
+        let asset = &release.assets[0].browser_download_url;
+        assert_eq!(download_service.fetch_bytes(asset).await.unwrap(), b"pack");
+        let outside_asset = &release.assets[1].browser_download_url;
+        assert!(download_service.fetch_bytes(outside_asset).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_manifest_cannot_point_at_local_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside.zip");
+        std::fs::write(&outside, b"secret").unwrap();
+
+        let provider = ManifestReleaseService::new(
+            "https://example.com/releases.json".to_string(),
+            "owner/repo".to_string(),
+        );
+        assert_eq!(provider.local_root(), None);
+
+        let download_service = DownloadService::with_local_roots(provider.local_root().into_iter().collect());
+        let dest = dir.path().join("download.zip");
+        assert!(download_service.download_simple(&file_url(&outside), dest.clone()).await.is_err());
+        assert!(!dest.exists());
+    }
+}
@@ -1,13 +1,59 @@
 pub mod game_service;
+pub mod http_client_service;
 pub mod download_service;
+pub mod download_queue_service;
+pub mod progress_reporter;
 pub mod translation_service;
 pub mod github_service;
+pub mod gitea_service;
+pub mod manifest_release_service;
+pub mod local_release_service;
+pub mod release_provider;
 pub mod file_service;
+pub mod archive_service;
+pub mod signature_service;
 pub mod updater_service;
+pub mod plugin_service;
+pub mod config_merge_service;
+pub mod delta_service;
+pub mod translation_text_service;
+pub mod override_service;
+pub mod translation_index_service;
+pub mod diagnostics_service;
+pub mod logging_service;
+pub mod locale_service;
+pub mod history_service;
+pub mod scheduler_service;
+pub mod tray_service;
+pub mod game_monitor_service;
+pub mod doorstop_service;
 
 pub use game_service::GameService;
+pub use http_client_service::HttpClientService;
 pub use download_service::DownloadService;
+pub use download_queue_service::DownloadQueueService;
+pub use progress_reporter::ProgressReporter;
 pub use translation_service::TranslationService;
 pub use github_service::GitHubService;
+pub use gitea_service::GiteaService;
+pub use manifest_release_service::ManifestReleaseService;
+pub use local_release_service::LocalReleaseService;
+pub use release_provider::{create_release_provider, ReleaseProvider};
 pub use file_service::FileService;
+pub use archive_service::ArchiveService;
+pub use signature_service::SignatureService;
 pub use updater_service::{UpdaterService, AppUpdateInfo};
+pub use plugin_service::PluginService;
+pub use config_merge_service::ConfigMergeService;
+pub use delta_service::DeltaService;
+pub use translation_text_service::TranslationTextService;
+pub use override_service::OverrideService;
+pub use translation_index_service::TranslationIndexService;
+pub use diagnostics_service::DiagnosticsService;
+pub use logging_service::LoggingService;
+pub use locale_service::{Locale, LocaleService};
+pub use history_service::{HistoryRecorder, HistoryService};
+pub use scheduler_service::SchedulerService;
+pub use tray_service::TrayService;
+pub use game_monitor_service::GameMonitorService;
+pub use doorstop_service::DoorstopService;
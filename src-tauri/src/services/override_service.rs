@@ -0,0 +1,313 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, OverrideReport, TranslationOverride};
+use crate::services::{FileService, GameService, TranslationTextService};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// File chứa các câu sửa không có trong bản việt hóa (XUnity đọc mọi file .txt trong thư mục Text)
+const OVERRIDE_FILE_NAME: &str = "zz_user_overrides.txt";
+
+pub struct OverrideService;
+
+impl OverrideService {
+    /// Đọc danh sách bản sửa (lưu ngoài thư mục game nên không bị mất khi cập nhật)
+    pub fn load() -> Result<Vec<TranslationOverride>, AppError> {
+        let store_path = Self::get_store_path()?;
+        if !store_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&store_path)
+            .map_err(|e| err!("error.read_overrides", error = e))?;
+
+        serde_json::from_str(&content)
+            .map_err(|e| err!("error.parse_overrides", error = e))
+    }
+
+    fn save(overrides: &[TranslationOverride]) -> Result<(), AppError> {
+        let store_path = Self::get_store_path()?;
+
+        if let Some(parent) = store_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| err!("error.create_config_dir", error = e))?;
+        }
+
+        let content = serde_json::to_string_pretty(overrides)
+            .map_err(|e| err!("error.serialize_overrides", error = e))?;
+
+        fs::write(&store_path, content)
+            .map_err(|e| err!("error.write_overrides", error = e))
+    }
+
+    fn get_store_path() -> Result<PathBuf, AppError> {
+        Ok(AppConfig::get_data_dir()?.join("translation_overrides.json"))
+    }
+
+    /// Thêm bản sửa mới
+    pub fn add_override(source: &str, translation: &str) -> Result<(), AppError> {
+        let mut overrides = Self::load()?;
+        if overrides.iter().any(|o| o.source == source) {
+            return Err(err!("error.override_exists", source = source));
+        }
+
+        overrides.push(TranslationOverride {
+            source: source.to_string(),
+            translation: translation.to_string(),
+            pack_translation: None,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+        });
+
+        Self::save(&overrides)
+    }
+
+    /// Sửa bản dịch của một bản sửa đã có
+    pub fn update_override(source: &str, translation: &str) -> Result<(), AppError> {
+        let mut overrides = Self::load()?;
+        let item = overrides.iter_mut()
+            .find(|o| o.source == source)
+            .ok_or_else(|| err!("error.override_not_found", source = source))?;
+
+        item.translation = translation.to_string();
+        item.updated_at = chrono::Utc::now().to_rfc3339();
+
+        Self::save(&overrides)
+    }
+
+    /// Xóa bản sửa và khôi phục bản dịch gốc của bản việt hóa trong thư mục game
+    pub fn delete_override(game_path: Option<&Path>, source: &str) -> Result<(), AppError> {
+        if game_path.is_some() {
+            GameService::ensure_game_not_running()?;
+        }
+
+        let mut overrides = Self::load()?;
+        let index = overrides.iter()
+            .position(|o| o.source == source)
+            .ok_or_else(|| err!("error.override_not_found", source = source))?;
+        let removed = overrides.remove(index);
+        Self::save(&overrides)?;
+
+        let Some(game_path) = game_path else {
+            return Ok(());
+        };
+
+        Self::restore_pack_translation(game_path, &removed)?;
+
+        // Ghi lại file bản sửa riêng để bỏ câu vừa xóa
+        Self::apply_overrides(game_path, Some(&[]))?;
+        Ok(())
+    }
+
+    /// Đưa các dòng đang dùng bản sửa về bản dịch của bản việt hóa
+    fn restore_pack_translation(game_path: &Path, removed: &TranslationOverride) -> Result<(), AppError> {
+        let Some(pack_translation) = &removed.pack_translation else {
+            return Ok(());
+        };
+
+        for path in TranslationTextService::collect_text_files(game_path)? {
+            if Self::is_override_file(&path) {
+                continue;
+            }
+
+            Self::rewrite_lines(&path, |line_source, value| {
+                if line_source == removed.source && value == removed.translation {
+                    Some(pack_translation.clone())
+                } else {
+                    None
+                }
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Áp dụng các bản sửa lên file text của bản việt hóa
+    ///
+    /// `fresh_files`: các file vừa được bản việt hóa ghi mới (đường dẫn tương đối so với
+    /// thư mục game), None nghĩa là toàn bộ. Chỉ những file này mới dùng để phát hiện bản
+    /// sửa đã lỗi thời, vì các file khác có thể đã chứa bản sửa từ lần áp dụng trước.
+    #[tracing::instrument(skip(fresh_files))]
+    pub fn apply_overrides(game_path: &Path, fresh_files: Option<&[String]>) -> Result<OverrideReport, AppError> {
+        GameService::ensure_game_not_running()?;
+
+        let mut overrides = Self::load()?;
+        let report = Self::apply_to_files(game_path, &mut overrides, fresh_files)?;
+        Self::save(&overrides)?;
+
+        tracing::info!(
+            applied = report.applied,
+            appended = report.appended,
+            obsolete = report.obsolete.len(),
+            "Applied translation overrides"
+        );
+        Ok(report)
+    }
+
+    /// Ghi các bản sửa vào file text, cập nhật bản dịch gốc của bản việt hóa trong `overrides`
+    fn apply_to_files(
+        game_path: &Path,
+        overrides: &mut [TranslationOverride],
+        fresh_files: Option<&[String]>,
+    ) -> Result<OverrideReport, AppError> {
+        let mut report = OverrideReport::default();
+
+        let by_source: HashMap<String, usize> = overrides.iter()
+            .enumerate()
+            .map(|(index, o)| (o.source.clone(), index))
+            .collect();
+        let mut found = vec![false; overrides.len()];
+        let mut obsolete = Vec::new();
+
+        for path in TranslationTextService::collect_text_files(game_path)? {
+            if Self::is_override_file(&path) {
+                continue;
+            }
+
+            let relative = TranslationTextService::relative_path(game_path, &path);
+            let is_fresh = fresh_files
+                .map(|files| files.contains(&relative))
+                .unwrap_or(true);
+
+            let applied = Self::rewrite_lines(&path, |source, value| {
+                let index = *by_source.get(source)?;
+                found[index] = true;
+                let item = &mut overrides[index];
+
+                if is_fresh {
+                    item.pack_translation = Some(value.to_string());
+                    if value == item.translation {
+                        // Bản việt hóa đã dịch giống bản sửa
+                        if !obsolete.contains(&item.source) {
+                            obsolete.push(item.source.clone());
+                        }
+                        return None;
+                    }
+                } else if item.pack_translation.is_none() && value != item.translation {
+                    // Bản sửa chưa từng được áp dụng lên dòng này nên đây là bản dịch gốc,
+                    // cần giữ lại để khôi phục khi xóa bản sửa
+                    item.pack_translation = Some(value.to_string());
+                }
+
+                if value == item.translation {
+                    None
+                } else {
+                    Some(item.translation.clone())
+                }
+            })?;
+            report.applied += applied;
+        }
+        report.obsolete = obsolete;
+
+        // Câu không có trong bản việt hóa thì ghi vào file riêng
+        let missing: Vec<&TranslationOverride> = overrides.iter()
+            .zip(&found)
+            .filter(|(_, found)| !**found)
+            .map(|(item, _)| item)
+            .collect();
+
+        if let Some(text_dir) = Self::find_text_dir(game_path) {
+            let override_file = text_dir.join(OVERRIDE_FILE_NAME);
+
+            if missing.is_empty() {
+                FileService::remove_path(&override_file)?;
+            } else {
+                let mut content = String::from("// Bản sửa của người dùng (PriconneVH)\n");
+                for item in &missing {
+                    content.push_str(&TranslationTextService::format_line(&item.source, &item.translation));
+                    content.push('\n');
+                }
+
+                fs::write(&override_file, content)
+                    .map_err(|e| err!("error.write_overrides_file", error = e))?;
+                report.appended = missing.len();
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Sửa từng dòng dịch trong file, trả về số dòng đã thay đổi
+    fn rewrite_lines<F>(path: &Path, mut replace: F) -> Result<usize, AppError>
+    where
+        F: FnMut(&str, &str) -> Option<String>,
+    {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            // Bỏ qua file không phải UTF-8
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return Ok(0),
+            Err(e) => return Err(err!("error.read_translation_file", error = e)),
+        };
+
+        let mut changed = 0;
+        let lines: Vec<String> = content.split('\n')
+            .map(|line| {
+                let replacement = TranslationTextService::parse_line(line)
+                    .and_then(|(source, value)| replace(&source, &value))
+                    .and_then(|translation| TranslationTextService::replace_translation(line, &translation));
+
+                match replacement {
+                    Some(new_line) => {
+                        changed += 1;
+                        new_line
+                    }
+                    None => line.to_string(),
+                }
+            })
+            .collect();
+
+        if changed > 0 {
+            fs::write(path, lines.join("\n"))
+                .map_err(|e| err!("error.write_translation_file", error = e))?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Thư mục Text đầu tiên trong BepInEx/Translation/<ngôn ngữ>/Text
+    fn find_text_dir(game_path: &Path) -> Option<PathBuf> {
+        let translation_dir = TranslationTextService::translation_dir(game_path);
+        let mut languages: Vec<PathBuf> = fs::read_dir(&translation_dir).ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().join("Text"))
+            .filter(|path| path.is_dir())
+            .collect();
+
+        languages.sort();
+        languages.into_iter().next()
+    }
+
+    fn is_override_file(path: &Path) -> bool {
+        path.file_name()
+            .map(|name| name == OVERRIDE_FILE_NAME)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleting_obsolete_override_keeps_new_pack_text() {
+        let game = tempfile::tempdir().unwrap();
+        let text_dir = TranslationTextService::translation_dir(game.path()).join("vi").join("Text");
+        fs::create_dir_all(&text_dir).unwrap();
+        let file = text_dir.join("a.txt");
+
+        // Bản việt hóa mới đã dịch giống bản sửa, bản dịch gốc lưu lại là của bản cũ
+        fs::write(&file, "こんにちは=chào bạn\n").unwrap();
+        let mut overrides = vec![TranslationOverride {
+            source: "こんにちは".to_string(),
+            translation: "chào bạn".to_string(),
+            pack_translation: Some("xin chào".to_string()),
+            updated_at: String::new(),
+        }];
+
+        let report = OverrideService::apply_to_files(game.path(), &mut overrides, None).unwrap();
+        assert_eq!(report.obsolete, vec!["こんにちは".to_string()]);
+        assert_eq!(overrides[0].pack_translation.as_deref(), Some("chào bạn"));
+
+        OverrideService::restore_pack_translation(game.path(), &overrides[0]).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "こんにちは=chào bạn\n");
+    }
+}
@@ -0,0 +1,385 @@
+use crate::err;
+use crate::models::{AppError, PluginInfo};
+use crate::services::translation_service::TranslationInfo;
+use crate::services::{ArchiveService, FileService, GameService};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DISABLED_SUFFIX: &str = ".disabled";
+
+/// Plugin của người dùng được giữ lại khi cập nhật bản việt hóa
+#[derive(Debug, Default)]
+pub struct PreservedPlugins {
+    pub user_files: Vec<String>,
+    pub disabled_pack_files: Vec<String>,
+}
+
+pub struct PluginService;
+
+impl PluginService {
+    /// Thư mục BepInEx/plugins của game
+    pub fn plugins_dir(game_path: &Path) -> PathBuf {
+        game_path.join("BepInEx").join("plugins")
+    }
+
+    /// Liệt kê các plugin (.dll) trong BepInEx/plugins
+    pub fn list_plugins(game_path: &Path) -> Result<Vec<PluginInfo>, AppError> {
+        let plugins_dir = Self::plugins_dir(game_path);
+        if !plugins_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let pack_files = Self::pack_plugin_files(game_path);
+        let mut plugins = Vec::new();
+
+        for file in Self::collect_plugin_files(&plugins_dir)? {
+            let (key, enabled) = match file.strip_suffix(DISABLED_SUFFIX) {
+                Some(key) => (key.to_string(), false),
+                None => (file.clone(), true),
+            };
+
+            if !key.to_lowercase().ends_with(".dll") {
+                continue;
+            }
+
+            let path = plugins_dir.join(&file);
+            let size = fs::metadata(&path)
+                .map_err(|e| err!("error.file_metadata", error = e))?
+                .len();
+            let metadata = Self::read_assembly_metadata(&path);
+
+            let name = metadata.get("FileDescription")
+                .or_else(|| metadata.get("ProductName"))
+                .cloned()
+                .unwrap_or_else(|| {
+                    Path::new(&key)
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_else(|| key.clone())
+                });
+
+            let version = metadata.get("Assembly Version")
+                .or_else(|| metadata.get("FileVersion"))
+                .or_else(|| metadata.get("ProductVersion"))
+                .cloned();
+
+            plugins.push(PluginInfo {
+                from_translation_pack: pack_files.contains(&key),
+                file_name: key,
+                name,
+                version,
+                description: metadata.get("Comments").cloned(),
+                company: metadata.get("CompanyName").cloned(),
+                size,
+                enabled,
+            });
+        }
+
+        plugins.sort_by_key(|p| p.file_name.to_lowercase());
+        Ok(plugins)
+    }
+
+    /// Bật/tắt plugin bằng cách đổi tên file (.dll <-> .dll.disabled)
+    #[tracing::instrument]
+    pub fn set_plugin_enabled(game_path: &Path, file_name: &str, enabled: bool) -> Result<(), AppError> {
+        GameService::ensure_game_not_running()?;
+
+        let relative = FileService::safe_relative_path(file_name)?;
+        let plugins_dir = Self::plugins_dir(game_path);
+
+        let enabled_path = plugins_dir.join(&relative);
+        let disabled_path = plugins_dir.join(format!("{}{}", relative.to_string_lossy(), DISABLED_SUFFIX));
+
+        let (from, to) = if enabled {
+            (disabled_path, enabled_path)
+        } else {
+            (enabled_path, disabled_path)
+        };
+
+        if to.exists() && !from.exists() {
+            // Đã ở trạng thái mong muốn
+            return Ok(());
+        }
+
+        if !from.exists() {
+            return Err(err!("error.plugin_not_found", name = file_name));
+        }
+
+        fs::rename(&from, &to)
+            .map_err(|e| err!("error.rename_plugin", error = e))
+    }
+
+    /// Cài plugin từ file nén (zip, 7z, tar.zst) vào BepInEx/plugins
+    #[tracing::instrument]
+    pub fn install_plugin_zip(game_path: &Path, zip_path: &Path) -> Result<Vec<String>, AppError> {
+        GameService::ensure_game_not_running()?;
+
+        let plugins_dir = Self::plugins_dir(game_path);
+        if !game_path.join("BepInEx").exists() {
+            return Err(err!("error.bepinex_not_installed"));
+        }
+
+        let temp_dir = std::env::temp_dir().join("priconevh_plugin_temp");
+        if temp_dir.exists() {
+            FileService::remove_path(&temp_dir)?;
+        }
+        ArchiveService::extract(zip_path, &temp_dir, |_, _| {})?;
+
+        let result = Self::install_extracted_plugin(&temp_dir, zip_path, &plugins_dir, game_path);
+        FileService::remove_path(&temp_dir)?;
+        result
+    }
+
+    fn install_extracted_plugin(
+        extract_dir: &Path,
+        zip_path: &Path,
+        plugins_dir: &Path,
+        game_path: &Path,
+    ) -> Result<Vec<String>, AppError> {
+        // Zip có thể chứa sẵn cấu trúc BepInEx/plugins, plugins/ hoặc chỉ có các file dll
+        let (source, dest) = if extract_dir.join("BepInEx").join("plugins").is_dir() {
+            (extract_dir.join("BepInEx").join("plugins"), plugins_dir.to_path_buf())
+        } else if extract_dir.join("plugins").is_dir() {
+            (extract_dir.join("plugins"), plugins_dir.to_path_buf())
+        } else {
+            let folder_name = zip_path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "plugin".to_string());
+            (extract_dir.to_path_buf(), plugins_dir.join(folder_name))
+        };
+
+        let files = Self::collect_plugin_files(&source)?;
+        if !files.iter().any(|f| f.to_lowercase().ends_with(".dll")) {
+            return Err(err!("error.no_plugin_dll"));
+        }
+
+        // Không cho ghi đè plugin của bản việt hóa
+        let pack_files = Self::pack_plugin_files(game_path);
+        let dest_prefix = Self::relative_key(plugins_dir, &dest).unwrap_or_default();
+        let mut installed = Vec::new();
+
+        for file in &files {
+            let key = if dest_prefix.is_empty() {
+                file.clone()
+            } else {
+                format!("{}/{}", dest_prefix, file)
+            };
+
+            if pack_files.contains(&key) {
+                return Err(err!("error.plugin_conflict", file = key));
+            }
+            installed.push(key);
+        }
+
+        FileService::copy_dir_recursive(&source, &dest)?;
+
+        Ok(installed)
+    }
+
+    /// Sao lưu plugin không thuộc bản việt hóa trước khi xóa BepInEx
+    pub fn preserve_user_plugins(
+        game_path: &Path,
+        pack_files: &[String],
+        stash_dir: &Path,
+    ) -> Result<PreservedPlugins, AppError> {
+        let plugins_dir = Self::plugins_dir(game_path);
+        let mut preserved = PreservedPlugins::default();
+
+        if !plugins_dir.exists() {
+            return Ok(preserved);
+        }
+
+        if stash_dir.exists() {
+            FileService::remove_path(stash_dir)?;
+        }
+
+        for file in Self::collect_plugin_files(&plugins_dir)? {
+            let key = file.strip_suffix(DISABLED_SUFFIX).unwrap_or(&file);
+
+            if pack_files.iter().any(|p| p == key) {
+                // Plugin của bản việt hóa: chỉ ghi nhớ trạng thái tắt
+                if file.ends_with(DISABLED_SUFFIX) {
+                    preserved.disabled_pack_files.push(key.to_string());
+                }
+                continue;
+            }
+
+            let dest = stash_dir.join(&file);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| err!("error.create_dir", error = e))?;
+            }
+            fs::copy(plugins_dir.join(&file), &dest)
+                .map_err(|e| err!("error.preserve_plugin", file = file, error = e))?;
+
+            preserved.user_files.push(file);
+        }
+
+        Ok(preserved)
+    }
+
+    /// Khôi phục plugin của người dùng sau khi cài bản việt hóa mới
+    pub fn restore_user_plugins(
+        game_path: &Path,
+        stash_dir: &Path,
+        preserved: &PreservedPlugins,
+    ) -> Result<(), AppError> {
+        let plugins_dir = Self::plugins_dir(game_path);
+
+        for file in &preserved.user_files {
+            let key = file.strip_suffix(DISABLED_SUFFIX).unwrap_or(file);
+            let dest = plugins_dir.join(file);
+
+            // Bản việt hóa mới đã có file này thì ưu tiên bản mới
+            if plugins_dir.join(key).exists() {
+                continue;
+            }
+
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| err!("error.create_dir", error = e))?;
+            }
+            fs::copy(stash_dir.join(file), &dest)
+                .map_err(|e| err!("error.restore_plugin", file = file, error = e))?;
+        }
+
+        for key in &preserved.disabled_pack_files {
+            if plugins_dir.join(key).exists() {
+                Self::set_plugin_enabled(game_path, key, false)?;
+            }
+        }
+
+        if stash_dir.exists() {
+            FileService::remove_path(stash_dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Liệt kê đệ quy các file trong thư mục plugin (đường dẫn tương đối, dùng '/')
+    pub fn collect_plugin_files(dir: &Path) -> Result<Vec<String>, AppError> {
+        let mut files = Vec::new();
+        if dir.exists() {
+            Self::collect_files_recursive(dir, dir, &mut files)?;
+        }
+        Ok(files)
+    }
+
+    fn collect_files_recursive(base: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), AppError> {
+        for entry in fs::read_dir(dir)
+            .map_err(|e| err!("error.read_dir", error = e))?
+        {
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
+            let path = entry.path();
+
+            if path.is_dir() {
+                Self::collect_files_recursive(base, &path, files)?;
+            } else if let Some(key) = Self::relative_key(base, &path) {
+                files.push(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Danh sách file plugin thuộc bản việt hóa đang cài
+    fn pack_plugin_files(game_path: &Path) -> Vec<String> {
+        TranslationInfo::load(game_path)
+            .map(|info| info.plugins)
+            .unwrap_or_default()
+    }
+
+    fn relative_key(base: &Path, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(base).ok()?;
+        let parts: Vec<String> = relative.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+        Some(parts.join("/"))
+    }
+
+    /// Đọc thông tin assembly từ version resource (VS_VERSIONINFO) của file dll
+    pub fn read_assembly_metadata(path: &Path) -> HashMap<String, String> {
+        let data = match fs::read(path) {
+            Ok(data) => data,
+            Err(_) => return HashMap::new(),
+        };
+
+        let marker: Vec<u8> = "VS_VERSION_INFO"
+            .encode_utf16()
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+
+        let mut strings = HashMap::new();
+
+        if let Some(pos) = data.windows(marker.len()).position(|w| w == marker.as_slice()) {
+            if pos >= 6 {
+                Self::parse_version_block(&data, pos - 6, &mut strings);
+            }
+        }
+
+        strings
+    }
+
+    /// Duyệt cây VS_VERSIONINFO, gom các cặp key/value trong StringFileInfo
+    fn parse_version_block(data: &[u8], offset: usize, strings: &mut HashMap<String, String>) -> Option<usize> {
+        let read_u16 = |at: usize| -> Option<u16> {
+            data.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+        };
+        let align4 = |at: usize| (at + 3) & !3;
+
+        let length = read_u16(offset)? as usize;
+        let value_length = read_u16(offset + 2)? as usize;
+        let value_type = read_u16(offset + 4)?;
+        if length < 6 {
+            return None;
+        }
+        let end = (offset + length).min(data.len());
+
+        let (key, key_end) = Self::read_utf16_string(data, offset + 6, end)?;
+        let value_start = align4(key_end);
+
+        // wType = 1: giá trị dạng text (độ dài tính theo ký tự UTF-16)
+        let value_bytes = if value_type == 1 { value_length * 2 } else { value_length };
+
+        let is_container = matches!(key.as_str(), "VS_VERSION_INFO" | "StringFileInfo")
+            || (value_length == 0 && key.len() == 8 && key.chars().all(|c| c.is_ascii_hexdigit()));
+
+        if is_container {
+            let mut child = align4(value_start + value_bytes);
+            while child + 6 < end {
+                let child_length = Self::parse_version_block(data, child, strings)?;
+                child = align4(child + child_length);
+            }
+        } else if value_type == 1 && value_length > 0 {
+            if let Some((value, _)) = Self::read_utf16_string(data, value_start, end) {
+                let value = value.trim().to_string();
+                if !value.is_empty() {
+                    strings.insert(key, value);
+                }
+            }
+        }
+
+        Some(length)
+    }
+
+    fn read_utf16_string(data: &[u8], start: usize, end: usize) -> Option<(String, usize)> {
+        let mut units = Vec::new();
+        let mut at = start;
+
+        while at + 2 <= end {
+            let unit = u16::from_le_bytes([data[at], data[at + 1]]);
+            at += 2;
+            if unit == 0 {
+                return Some((String::from_utf16_lossy(&units), at));
+            }
+            units.push(unit);
+        }
+
+        if units.is_empty() {
+            None
+        } else {
+            Some((String::from_utf16_lossy(&units), at))
+        }
+    }
+}
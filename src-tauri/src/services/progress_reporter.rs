@@ -0,0 +1,151 @@
+use crate::models::{ProgressEvent, ProgressPhase};
+use crate::services::DownloadService;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// Khoảng cách tối thiểu giữa hai lần gửi trong cùng một giai đoạn
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+// Lấy mẫu tốc độ sau mỗi khoảng này, làm mượt để ETA không nhảy liên tục
+const SPEED_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+const SPEED_SMOOTHING: f64 = 0.3;
+
+type Sink = Box<dyn FnMut(&ProgressEvent) + Send>;
+
+/// Gom tiến trình của một thao tác thành `ProgressEvent`, tính tốc độ và ETA
+///
+/// Đổi giai đoạn thì gửi ngay, cập nhật byte trong cùng giai đoạn thì gửi tối đa
+/// mỗi `EMIT_INTERVAL`. Clone dùng chung trạng thái để các bước con báo cùng một thao tác.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    state: Arc<Mutex<ReporterState>>,
+}
+
+struct ReporterState {
+    sink: Sink,
+    event: ProgressEvent,
+    last_emit: Option<Instant>,
+    last_sample: Option<(Instant, u64)>,
+    speed: f64,
+}
+
+impl ProgressReporter {
+    pub fn new(operation_id: impl Into<String>, sink: impl FnMut(&ProgressEvent) + Send + 'static) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReporterState {
+                sink: Box::new(sink),
+                event: ProgressEvent {
+                    operation_id: operation_id.into(),
+                    phase: ProgressPhase::Preparing,
+                    bytes_done: 0,
+                    bytes_total: 0,
+                    bytes_per_second: 0,
+                    eta_secs: None,
+                    current_file: None,
+                },
+                last_emit: None,
+                last_sample: None,
+                speed: 0.0,
+            })),
+        }
+    }
+
+    /// Reporter không gửi đi đâu
+    pub fn silent() -> Self {
+        Self::new(String::new(), |_| {})
+    }
+
+    /// Tạo id cho thao tác mới, vd: `install-1760832000000`
+    pub fn new_operation_id(kind: &str) -> String {
+        format!("{}-{}", kind, chrono::Utc::now().timestamp_millis())
+    }
+
+    pub fn operation_id(&self) -> String {
+        self.state.lock().unwrap().event.operation_id.clone()
+    }
+
+    /// Chuyển sang giai đoạn mới, số byte và tốc độ tính lại từ đầu
+    pub fn phase(&self, phase: ProgressPhase) {
+        self.phase_with_file(phase, None);
+    }
+
+    pub fn phase_with_file(&self, phase: ProgressPhase, current_file: Option<&str>) {
+        let mut state = self.state.lock().unwrap();
+        state.event.phase = phase;
+        state.event.bytes_done = 0;
+        state.event.bytes_total = 0;
+        state.event.bytes_per_second = 0;
+        state.event.eta_secs = None;
+        state.event.current_file = current_file.map(str::to_string);
+        state.last_sample = None;
+        state.speed = 0.0;
+        state.emit();
+    }
+
+    /// Cập nhật số byte của giai đoạn hiện tại
+    pub fn bytes(&self, done: u64, total: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.update_bytes(done, total);
+        let finished = total > 0 && done >= total;
+        state.emit_throttled(finished);
+    }
+
+    /// Cập nhật tiến trình tải, chuyển qua lại giữa `Downloading` và `Paused` theo trạng thái tạm dừng
+    pub fn download(&self, done: u64, total: u64) {
+        let phase = if DownloadService::is_paused(&self.operation_id()) {
+            ProgressPhase::Paused
+        } else {
+            ProgressPhase::Downloading
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let phase_changed = state.event.phase != phase;
+        if phase_changed {
+            state.event.phase = phase;
+            state.last_sample = None;
+            state.speed = 0.0;
+        }
+        state.update_bytes(done, total);
+        let finished = total > 0 && done >= total;
+        state.emit_throttled(phase_changed || finished);
+    }
+}
+
+impl ReporterState {
+    fn update_bytes(&mut self, done: u64, total: u64) {
+        let now = Instant::now();
+        match self.last_sample {
+            Some((sampled_at, sampled_bytes)) => {
+                let elapsed = now.duration_since(sampled_at);
+                if elapsed >= SPEED_SAMPLE_INTERVAL {
+                    let current = done.saturating_sub(sampled_bytes) as f64 / elapsed.as_secs_f64();
+                    self.speed = if self.speed > 0.0 {
+                        self.speed + (current - self.speed) * SPEED_SMOOTHING
+                    } else {
+                        current
+                    };
+                    self.last_sample = Some((now, done));
+                }
+            }
+            None => self.last_sample = Some((now, done)),
+        }
+
+        let paused = self.event.phase == ProgressPhase::Paused;
+        self.event.bytes_done = done;
+        self.event.bytes_total = total;
+        self.event.bytes_per_second = if paused { 0 } else { self.speed as u64 };
+        self.event.eta_secs = (!paused && total > done && self.speed > 0.0)
+            .then(|| ((total - done) as f64 / self.speed).ceil() as u64);
+    }
+
+    fn emit_throttled(&mut self, force: bool) {
+        let due = self.last_emit.is_none_or(|last_emit| last_emit.elapsed() >= EMIT_INTERVAL);
+        if force || due {
+            self.emit();
+        }
+    }
+
+    fn emit(&mut self) {
+        self.last_emit = Some(Instant::now());
+        (self.sink)(&self.event);
+    }
+}
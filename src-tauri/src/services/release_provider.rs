@@ -0,0 +1,40 @@
+use crate::err;
+use crate::models::{AppConfig, AppError, Release, ReleaseSource};
+use crate::services::{GiteaService, GitHubService, LocalReleaseService, ManifestReleaseService};
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+/// Nguồn lấy danh sách release, chọn theo `AppConfig.release_source`
+#[async_trait]
+pub trait ReleaseProvider: Send + Sync {
+    /// Repo dạng owner/name, dùng cho mẫu URL của mirror
+    fn repo(&self) -> &str;
+
+    /// Thư mục trên máy chứa asset của nguồn này, chỉ trong đó mới được tải qua URL file://
+    fn local_root(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Tất cả release, mới nhất trước
+    async fn get_all_releases(&self) -> Result<Vec<Release>, AppError>;
+
+    async fn get_latest_release(&self) -> Result<Release, AppError> {
+        self.get_all_releases()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| err!("error.no_releases"))
+    }
+}
+
+/// Tạo provider theo cài đặt
+pub fn create_release_provider(config: &AppConfig) -> Box<dyn ReleaseProvider> {
+    let repo = config.github_repo.clone();
+
+    match &config.release_source {
+        ReleaseSource::GitHub => Box::new(GitHubService::new(repo)),
+        ReleaseSource::Gitea { base_url } => Box::new(GiteaService::new(base_url.clone(), repo)),
+        ReleaseSource::Manifest { url } => Box::new(ManifestReleaseService::new(url.clone(), repo)),
+        ReleaseSource::Local { path } => Box::new(LocalReleaseService::new(path.clone(), repo)),
+    }
+}
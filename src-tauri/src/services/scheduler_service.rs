@@ -0,0 +1,181 @@
+use crate::commands::{update_translation, TranslationState};
+use crate::models::{AppConfig, AppError};
+use crate::services::translation_service::TranslationInfo;
+use crate::services::{GameService, ProgressReporter, TranslationService, UpdaterService};
+use crate::t;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::Mutex;
+
+// Đọc lại config sau mỗi lần chờ để thay đổi trong cài đặt có hiệu lực ngay
+const TICK: Duration = Duration::from_secs(10 * 60);
+
+/// Tác vụ chạy nền kiểm tra cập nhật bản việt hóa và app
+pub struct SchedulerService {
+    app: AppHandle,
+    translation_service: Arc<Mutex<TranslationService>>,
+    updater_service: Arc<Mutex<UpdaterService>>,
+    // Chỉ thông báo một lần cho mỗi phiên bản
+    notified_translation: Option<String>,
+    notified_app: Option<String>,
+}
+
+impl SchedulerService {
+    pub fn new(
+        app: AppHandle,
+        translation_service: Arc<Mutex<TranslationService>>,
+        updater_service: Arc<Mutex<UpdaterService>>,
+    ) -> Self {
+        Self {
+            app,
+            translation_service,
+            updater_service,
+            notified_translation: None,
+            notified_app: None,
+        }
+    }
+
+    /// Chạy kiểm tra định kỳ trong nền
+    pub fn start(self) {
+        tauri::async_runtime::spawn(self.run());
+    }
+
+    async fn run(mut self) {
+        let config = AppConfig::load().unwrap_or_default();
+        if config.check_update_on_startup {
+            self.check_all(config.auto_update).await;
+        }
+
+        // Kiểm tra định kỳ chỉ theo `update_check_interval_hours`, tính từ lúc mở app
+        // kể cả khi tắt kiểm tra lúc khởi động
+        let mut last_check = Instant::now();
+        loop {
+            tokio::time::sleep(TICK).await;
+
+            let config = AppConfig::load().unwrap_or_default();
+            if config.update_check_interval_hours == 0 {
+                continue;
+            }
+
+            let interval = Duration::from_secs(config.update_check_interval_hours * 3600);
+            if last_check.elapsed() >= interval {
+                self.check_all(config.auto_update).await;
+                last_check = Instant::now();
+            }
+        }
+    }
+
+    /// Kiểm tra cập nhật bản việt hóa và app, trả về true nếu có bản mới
+    ///
+    /// `auto_apply`: tự cài bản việt hóa mới nếu game không chạy
+    #[tracing::instrument(name = "update_check", skip(self))]
+    pub async fn check_all(&mut self, auto_apply: bool) -> bool {
+        let translation_found = match self.check_translation_update(auto_apply).await {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!(error = %e, "Translation update check failed");
+                false
+            }
+        };
+
+        let app_found = match self.check_app_update().await {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::warn!(error = %e, "App update check failed");
+                false
+            }
+        };
+
+        translation_found || app_found
+    }
+
+    async fn check_translation_update(&mut self, auto_apply: bool) -> Result<bool, AppError> {
+        let config = AppConfig::load()?;
+        let Some(game_path) = config.game_path else {
+            return Ok(false);
+        };
+
+        // Chưa cài bản việt hóa thì không có gì để cập nhật
+        let Some(info) = TranslationInfo::load(&game_path) else {
+            return Ok(false);
+        };
+
+        // Chỉ giữ lock khi kiểm tra, không chặn thao tác trên giao diện trong lúc chờ mạng
+        let latest = self.translation_service.lock().await
+            .check_for_updates(&info.version)
+            .await?;
+        let Some(version) = latest else {
+            return Ok(false);
+        };
+        tracing::info!(current = %info.version, latest = %version.version, "Translation update available");
+        let _ = self.app.emit("translation-update-available", &version);
+
+        if !auto_apply || GameService::is_game_running() {
+            if self.notified_translation.as_deref() != Some(version.version.as_str()) {
+                self.notify(
+                    &t!("notify.translation_available_title"),
+                    &t!("notify.translation_available_body", version = version.version),
+                );
+                self.notified_translation = Some(version.version.clone());
+            }
+            return Ok(true);
+        }
+
+        // Cài qua đúng lệnh giao diện dùng: chờ tới lượt sau thao tác đang chạy, ghi lịch sử,
+        // báo tiến trình và làm mới khay như khi người dùng bấm cập nhật
+        let result = update_translation(
+            self.app.clone(),
+            self.app.state::<TranslationState>(),
+            game_path.to_string_lossy().to_string(),
+            version.clone(),
+            Some(ProgressReporter::new_operation_id("auto-update")),
+        )
+        .await;
+
+        match result {
+            Ok(_) => {
+                tracing::info!(version = %version.version, "Automatic translation update finished");
+                self.notify(
+                    &t!("notify.translation_updated_title"),
+                    &t!("notify.translation_updated_body", version = version.version),
+                );
+                Ok(true)
+            }
+            Err(e) => {
+                self.notify(&t!("notify.translation_update_failed_title"), e.message());
+                Err(e)
+            }
+        }
+    }
+
+    async fn check_app_update(&mut self) -> Result<bool, AppError> {
+        let service = self.updater_service.lock().await;
+        let Some(update) = service.check_for_updates().await? else {
+            return Ok(false);
+        };
+        let _ = self.app.emit("app-update-available", &update);
+
+        if self.notified_app.as_deref() != Some(update.version.as_str()) {
+            self.notify(
+                &t!("notify.app_update_title"),
+                &t!("notify.app_update_body", version = update.version),
+            );
+            self.notified_app = Some(update.version.clone());
+        }
+
+        Ok(true)
+    }
+
+    pub fn notify(&self, title: &str, body: &str) {
+        if let Err(e) = self.app.notification()
+            .builder()
+            .title(title)
+            .body(body)
+            .show()
+        {
+            tracing::warn!(error = %e, "Failed to show notification");
+        }
+    }
+}
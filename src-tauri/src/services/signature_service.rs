@@ -0,0 +1,259 @@
+use crate::err;
+use crate::models::{AppConfig, AppError};
+use minisign_verify::{PublicKey, Signature};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Khóa ký bản việt hóa, build.rs lấy từ `keys/priconevh.pub` và `PRICONEVH_SIGNING_KEYS`
+const SIGNING_KEYS: &str = env!("PRICONEVH_TRUSTED_SIGNING_KEYS");
+
+/// Khóa ký bản cập nhật app, build.rs lấy từ `keys/priconevh-updater.pub` và `PRICONEVH_UPDATER_KEYS`
+///
+/// Tách khỏi khóa bản việt hóa để người giữ khóa ký bản việt hóa không ký được file cài đặt app.
+const UPDATER_KEYS: &str = env!("PRICONEVH_TRUSTED_UPDATER_KEYS");
+
+pub struct SignatureService;
+
+impl SignatureService {
+    /// Khóa tin cậy cho bản việt hóa, bỏ qua khóa không hợp lệ
+    pub fn trusted_keys() -> Vec<PublicKey> {
+        Self::parse_keys(SIGNING_KEYS)
+    }
+
+    /// Khóa tin cậy cho bản cập nhật app (manifest latest.json và file cài đặt)
+    pub fn updater_keys() -> Vec<PublicKey> {
+        Self::parse_keys(UPDATER_KEYS)
+    }
+
+    fn parse_keys(keys: &str) -> Vec<PublicKey> {
+        keys.split([',', '\n'])
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .filter_map(|key| match PublicKey::from_base64(key) {
+                Ok(public_key) => Some(public_key),
+                Err(e) => {
+                    tracing::warn!(key, error = %e, "Ignoring invalid signing key");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Cho phép cài bản chưa ký hoặc sai chữ ký (chỉ khi bật chế độ nhà phát triển)
+    pub fn allow_unsigned() -> bool {
+        AppConfig::load()
+            .map(|config| config.developer_mode && config.allow_unsigned_packs)
+            .unwrap_or(false)
+    }
+
+    /// Kiểm tra chữ ký của file bằng khóa bản việt hóa
+    pub fn verify_file(path: &Path, signature: &str) -> Result<(), AppError> {
+        Self::verify_file_with(path, signature, &Self::trusted_keys())
+    }
+
+    /// Kiểm tra chữ ký của file, đọc theo từng đoạn để không phải nạp cả file vào bộ nhớ
+    pub fn verify_file_with(path: &Path, signature: &str, keys: &[PublicKey]) -> Result<(), AppError> {
+        let signature = Self::decode_signature(signature)?;
+        if keys.is_empty() {
+            return Err(err!("error.no_trusted_keys"));
+        }
+
+        let mut last_error = None;
+        for key in keys {
+            let mut verifier = match key.verify_stream(&signature) {
+                Ok(verifier) => verifier,
+                Err(e) => {
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+
+            let mut file = fs::File::open(path)
+                .map_err(|e| err!("error.open_signed_file", error = e))?;
+            let mut buffer = vec![0u8; 64 * 1024];
+            loop {
+                let read = file.read(&mut buffer)
+                    .map_err(|e| err!("error.read_signed_file", error = e))?;
+                if read == 0 {
+                    break;
+                }
+                verifier.update(&buffer[..read]);
+            }
+
+            match verifier.finalize() {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(Self::verify_error(last_error))
+    }
+
+    /// Kiểm tra chữ ký của manifest.json bằng khóa bản việt hóa
+    pub fn verify_bytes(data: &[u8], signature: &str) -> Result<(), AppError> {
+        Self::verify_bytes_with(data, signature, &Self::trusted_keys())
+    }
+
+    /// Kiểm tra chữ ký của dữ liệu trong bộ nhớ
+    pub fn verify_bytes_with(data: &[u8], signature: &str, keys: &[PublicKey]) -> Result<(), AppError> {
+        let signature = Self::decode_signature(signature)?;
+        if keys.is_empty() {
+            return Err(err!("error.no_trusted_keys"));
+        }
+
+        let mut last_error = None;
+        for key in keys {
+            match key.verify(data, &signature, false) {
+                Ok(()) => return Ok(()),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(Self::verify_error(last_error))
+    }
+
+    /// Áp dụng chính sách chữ ký cho bản việt hóa: từ chối bản chưa ký hoặc sai chữ ký
+    /// trừ khi người dùng cho phép trong chế độ nhà phát triển
+    pub fn check_pack(path: &Path, signature: Option<&str>) -> Result<(), AppError> {
+        let result = match signature {
+            Some(signature) => Self::verify_file(path, signature),
+            None => Err(err!("error.pack_not_signed")),
+        };
+
+        match result {
+            Ok(()) => {
+                tracing::info!(file = %path.display(), "Signature verified");
+                Ok(())
+            }
+            Err(e) if Self::allow_unsigned() => {
+                tracing::warn!(file = %path.display(), error = %e, "Installing unverified pack (developer mode)");
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn decode_signature(signature: &str) -> Result<Signature, AppError> {
+        Signature::decode(signature)
+            .map_err(|e| err!("error.invalid_signature_file", error = e))
+    }
+
+    fn verify_error(error: Option<minisign_verify::Error>) -> AppError {
+        match error {
+            Some(minisign_verify::Error::UnexpectedKeyId) | None => {
+                err!("error.untrusted_key")
+            }
+            Some(minisign_verify::Error::InvalidSignature) => {
+                err!("error.signature_mismatch")
+            }
+            Some(e) => err!("error.signature_failed", error = e),
+        }
+    }
+}
+
+/// Cặp khóa minisign tạo trong test để ký dữ liệu giả lập bản phát hành
+#[cfg(test)]
+pub(crate) struct TestKey {
+    key_id: [u8; 8],
+    signing_key: ed25519_dalek::SigningKey,
+}
+
+#[cfg(test)]
+impl TestKey {
+    pub(crate) fn new(seed: u8) -> Self {
+        Self {
+            key_id: [seed; 8],
+            signing_key: ed25519_dalek::SigningKey::from_bytes(&[seed; 32]),
+        }
+    }
+
+    pub(crate) fn public_key(&self) -> PublicKey {
+        PublicKey::from_base64(&self.public_key_base64()).unwrap()
+    }
+
+    /// Dòng thứ hai của file .pub
+    pub(crate) fn public_key_base64(&self) -> String {
+        use base64::Engine;
+
+        let mut bytes = b"Ed".to_vec();
+        bytes.extend_from_slice(&self.key_id);
+        bytes.extend_from_slice(self.signing_key.verifying_key().as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    /// Nội dung file .minisig (chữ ký prehash như `minisign -S`)
+    pub(crate) fn sign(&self, data: &[u8]) -> String {
+        use base64::Engine;
+        use blake2::Digest;
+        use ed25519_dalek::Signer;
+
+        let signature = self.signing_key.sign(&blake2::Blake2b512::digest(data)).to_bytes();
+        let trusted_comment = "timestamp:0";
+        let global_signature = self.signing_key
+            .sign(&[&signature[..], trusted_comment.as_bytes()].concat())
+            .to_bytes();
+
+        let mut signature_bytes = b"ED".to_vec();
+        signature_bytes.extend_from_slice(&self.key_id);
+        signature_bytes.extend_from_slice(&signature);
+
+        let engine = base64::engine::general_purpose::STANDARD;
+        format!(
+            "untrusted comment: test\n{}\ntrusted comment: {}\n{}\n",
+            engine.encode(signature_bytes),
+            trusted_comment,
+            engine.encode(global_signature),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeds_separate_project_keys() {
+        // Bản debug có thể chưa cấu hình khóa, khóa đã cấu hình thì phải hợp lệ và không dùng chung
+        let count = |keys: &str| keys.split(',').filter(|key| !key.is_empty()).count();
+        assert_eq!(SignatureService::trusted_keys().len(), count(SIGNING_KEYS));
+        assert_eq!(SignatureService::updater_keys().len(), count(UPDATER_KEYS));
+        assert!(SIGNING_KEYS.split(',').filter(|key| !key.is_empty()).all(|key| !UPDATER_KEYS.contains(key)));
+    }
+
+    #[test]
+    fn parses_comma_separated_keys() {
+        let first = TestKey::new(4).public_key_base64();
+        let second = TestKey::new(5).public_key_base64();
+
+        let keys = SignatureService::parse_keys(&format!(" {}, ,not-a-key,{}\n", first, second));
+        assert_eq!(keys.len(), 2);
+        assert!(SignatureService::parse_keys("").is_empty());
+    }
+
+    #[test]
+    fn verifies_only_with_matching_key_set() {
+        let pack_key = TestKey::new(1);
+        let updater_key = TestKey::new(2);
+        let data = br#"{"version":"1.2.0"}"#;
+        let signature = updater_key.sign(data);
+
+        assert!(SignatureService::verify_bytes_with(data, &signature, &[updater_key.public_key()]).is_ok());
+        assert!(SignatureService::verify_bytes_with(data, &signature, &[pack_key.public_key()]).is_err());
+        assert!(SignatureService::verify_bytes_with(data, &signature, &[]).is_err());
+        assert!(SignatureService::verify_bytes_with(br#"{"version":"9.9.9"}"#, &signature, &[updater_key.public_key()])
+            .is_err());
+    }
+
+    #[test]
+    fn verifies_file_in_chunks() {
+        let key = TestKey::new(3);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("installer.msi");
+        let data = vec![7u8; 200 * 1024];
+        fs::write(&path, &data).unwrap();
+
+        assert!(SignatureService::verify_file_with(&path, &key.sign(&data), &[key.public_key()]).is_ok());
+        assert!(SignatureService::verify_file_with(&path, &key.sign(b"other"), &[key.public_key()]).is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::err;
+use crate::models::{AppError, SearchField, SearchMode, TranslationSearchResult};
+use crate::services::TranslationTextService;
+use regex::RegexBuilder;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_LIMIT: usize = 200;
+
+struct IndexEntry {
+    file: String,
+    line: usize,
+    source: String,
+    translation: String,
+    source_lower: String,
+    translation_lower: String,
+}
+
+struct LoadedIndex {
+    game_path: PathBuf,
+    signature: u64,
+    entries: Vec<IndexEntry>,
+}
+
+/// Chỉ mục các dòng dịch trong BepInEx/Translation để tìm kiếm nhanh
+pub struct TranslationIndexService {
+    index: Option<LoadedIndex>,
+}
+
+impl TranslationIndexService {
+    pub fn new() -> Self {
+        Self { index: None }
+    }
+
+    /// Tạo lại chỉ mục cho thư mục game, trả về số dòng đã index
+    pub fn build(&mut self, game_path: &Path) -> Result<usize, AppError> {
+        let files = TranslationTextService::collect_text_files(game_path)?;
+        let signature = Self::compute_signature(&files);
+        let mut entries = Vec::new();
+
+        for path in &files {
+            let file = TranslationTextService::relative_path(game_path, path);
+
+            for entry in TranslationTextService::read_entries(path)? {
+                entries.push(IndexEntry {
+                    file: file.clone(),
+                    line: entry.line,
+                    source_lower: entry.source.to_lowercase(),
+                    translation_lower: entry.translation.to_lowercase(),
+                    source: entry.source,
+                    translation: entry.translation,
+                });
+            }
+        }
+
+        let count = entries.len();
+        self.index = Some(LoadedIndex {
+            game_path: game_path.to_path_buf(),
+            signature,
+            entries,
+        });
+
+        Ok(count)
+    }
+
+    /// Tìm kiếm, tự tạo lại chỉ mục nếu file dịch đã thay đổi (cài/cập nhật/sửa bản dịch)
+    pub fn search(
+        &mut self,
+        game_path: &Path,
+        query: &str,
+        field: SearchField,
+        mode: SearchMode,
+        limit: Option<usize>,
+    ) -> Result<Vec<TranslationSearchResult>, AppError> {
+        if self.is_stale(game_path)? {
+            self.build(game_path)?;
+        }
+
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let index = self.index.as_ref()
+            .ok_or_else(|| err!("error.index_not_built"))?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT);
+        let query_lower = query.to_lowercase();
+        let query_chars: Vec<char> = query_lower.chars().collect();
+
+        let regex = match mode {
+            SearchMode::Regex => Some(
+                RegexBuilder::new(query)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| err!("error.invalid_regex", error = e))?,
+            ),
+            _ => None,
+        };
+
+        let mut results: Vec<TranslationSearchResult> = index.entries.iter()
+            .filter_map(|entry| {
+                let mut targets = Vec::new();
+                if field != SearchField::Translation {
+                    targets.push((&entry.source, &entry.source_lower));
+                }
+                if field != SearchField::Source {
+                    targets.push((&entry.translation, &entry.translation_lower));
+                }
+
+                let score = targets.iter()
+                    .filter_map(|(text, text_lower)| match mode {
+                        SearchMode::Plain => Self::plain_score(&query_lower, text_lower),
+                        SearchMode::Fuzzy => Self::fuzzy_score(&query_chars, text_lower),
+                        SearchMode::Regex => regex.as_ref()
+                            .filter(|regex| regex.is_match(text))
+                            .map(|_| 0),
+                    })
+                    .max()?;
+
+                Some(TranslationSearchResult {
+                    file: entry.file.clone(),
+                    line: entry.line,
+                    source: entry.source.clone(),
+                    translation: entry.translation.clone(),
+                    score,
+                })
+            })
+            .collect();
+
+        // Sắp xếp ổn định theo điểm để giữ thứ tự file/dòng khi bằng điểm
+        results.sort_by_key(|result| std::cmp::Reverse(result.score));
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    fn is_stale(&self, game_path: &Path) -> Result<bool, AppError> {
+        let Some(index) = &self.index else {
+            return Ok(true);
+        };
+
+        if index.game_path != game_path {
+            return Ok(true);
+        }
+
+        let files = TranslationTextService::collect_text_files(game_path)?;
+        Ok(Self::compute_signature(&files) != index.signature)
+    }
+
+    /// Hash đường dẫn, kích thước và thời gian sửa của các file dịch
+    fn compute_signature(files: &[PathBuf]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        for path in files {
+            path.hash(&mut hasher);
+            if let Ok(metadata) = std::fs::metadata(path) {
+                metadata.len().hash(&mut hasher);
+                if let Ok(modified) = metadata.modified() {
+                    modified.hash(&mut hasher);
+                }
+            }
+        }
+
+        hasher.finish()
+    }
+
+    fn plain_score(query: &str, text: &str) -> Option<i64> {
+        if text == query {
+            Some(1000)
+        } else if text.contains(query) {
+            Some(100)
+        } else {
+            None
+        }
+    }
+
+    /// Khớp các ký tự theo thứ tự (không cần liền nhau), ưu tiên ký tự liền nhau và khớp sớm
+    fn fuzzy_score(query: &[char], text: &str) -> Option<i64> {
+        let mut query_index = 0;
+        let mut score = 0i64;
+        let mut last_match: Option<usize> = None;
+        let mut text_length = 0;
+
+        for (index, c) in text.chars().enumerate() {
+            text_length = index + 1;
+            if query_index >= query.len() {
+                continue;
+            }
+
+            if c == query[query_index] {
+                score += 10;
+                if last_match.map(|last| last + 1 == index).unwrap_or(false) {
+                    score += 15;
+                }
+                if query_index == 0 {
+                    score -= index.min(20) as i64;
+                }
+
+                last_match = Some(index);
+                query_index += 1;
+            }
+        }
+
+        if query_index == query.len() {
+            Some(score - (text_length / 10) as i64)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for TranslationIndexService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
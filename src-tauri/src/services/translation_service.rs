@@ -1,144 +1,539 @@
-use crate::models::{GameInfo, TranslationVersion};
-use crate::services::{DownloadService, FileService, GitHubService};
-use std::path::Path;
+use crate::err;
+use crate::models::{AppError, ArchiveFormat, GameInfo, InstallResult, ProgressPhase, QueuedOperation, ReleaseAsset, SIGNATURE_EXTENSION, TranslationManifest, TranslationPack, TranslationVersion};
+use crate::services::delta_service::DeltaPlan;
+use crate::services::{
+    ArchiveService, ConfigMergeService, DeltaService, DoorstopService, DownloadQueueService, DownloadService,
+    FileService, GameService, OverrideService, PluginService, ProgressReporter, ReleaseProvider,
+    SignatureService,
+};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
+use tracing::Instrument;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TranslationInfo {
     pub version: String,
     pub installed_date: String,
     pub files: Vec<String>,
+    #[serde(default)]
+    pub plugins: Vec<String>,  // Các file trong BepInEx/plugins thuộc bản việt hóa
+}
+
+impl TranslationInfo {
+    /// Đọc translation_info.json trong thư mục game
+    pub fn load(game_path: &Path) -> Option<Self> {
+        let info_path = game_path.join("translation_info.json");
+        if !info_path.exists() {
+            return None;
+        }
+
+        let content = std::fs::read_to_string(info_path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+/// Các file thay đổi đã tải về thư mục tạm, chưa đụng tới thư mục game
+struct PreparedDelta {
+    target: TranslationManifest,
+    plan: DeltaPlan,
+    temp_dir: PathBuf,
+    staging_dir: PathBuf,
+    bytes_downloaded: u64,
 }
 
 pub struct TranslationService {
-    pub github_service: GitHubService,
+    release_provider: Box<dyn ReleaseProvider>,
     download_service: DownloadService,
 }
 
 impl TranslationService {
-    pub fn new(github_repo: String) -> Self {
+    pub fn new(release_provider: Box<dyn ReleaseProvider>) -> Self {
         Self {
-            github_service: GitHubService::new(github_repo),
-            download_service: DownloadService::new(),
+            download_service: DownloadService::with_local_roots(release_provider.local_root().into_iter().collect()),
+            release_provider,
         }
     }
 
+    /// Đổi nguồn release khi thay đổi cài đặt
+    pub fn set_release_provider(&mut self, release_provider: Box<dyn ReleaseProvider>) {
+        *self = Self::new(release_provider);
+    }
+
+    /// Danh sách các phiên bản việt hóa
+    pub async fn get_translation_pack(&self) -> Result<TranslationPack, AppError> {
+        let releases = self.release_provider.get_all_releases().await?;
+        
+        let mut pack = TranslationPack::new(self.release_provider.repo().to_string());
+        
+        if let Some(latest) = releases.first() {
+            pack.latest_version = latest.tag_name.clone();
+        }
+
+        pack.versions = releases
+            .into_iter()
+            .filter_map(|release| release.into_translation_version())
+            .collect();
+
+        Ok(pack)
+    }
+
     /// Cài đặt bản việt hóa
-    pub async fn install_translation<F>(
+    #[tracing::instrument(skip_all, fields(version = %version.version, game_path = %game_info.path.display()))]
+    pub async fn install_translation(
         &self,
         game_info: &GameInfo,
         version: &TranslationVersion,
-        progress_callback: F,
-    ) -> Result<(), String>
-    where
-        F: FnMut(&str, f32) + Send + 'static,
-    {
-        use std::sync::{Arc, Mutex};
-        
-        let progress = Arc::new(Mutex::new(progress_callback));
-        
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang chuẩn bị...", 0.0);
-        }
+        progress: &ProgressReporter,
+    ) -> Result<InstallResult, AppError> {
+        tracing::info!("Starting full install");
+        GameService::ensure_game_not_running()?;
+        progress.phase(ProgressPhase::Preparing);
 
         // Tạo thư mục temp
         let temp_dir = std::env::temp_dir().join("priconevh_temp");
         std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+            .map_err(|e| err!("error.create_temp_dir", error = e))?;
 
         // Download file
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang tải xuống bản việt hóa...", 10.0);
-        }
+        progress.phase_with_file(ProgressPhase::Downloading, Some(&version.asset_name));
         
-        let zip_path = temp_dir.join(format!("translation_{}.zip", version.version));
+        let extension = ArchiveFormat::from_file_name(&version.asset_name)
+            .unwrap_or(ArchiveFormat::Zip)
+            .extension();
+        // Tải vào thư mục của hàng đợi để tải tiếp được nếu app bị tắt giữa chừng
+        let zip_path = DownloadQueueService::downloads_dir()?
+            .join(format!("translation_{}.{}", version.version, extension));
+        let queue_id = DownloadQueueService::enqueue(
+            QueuedOperation::Translation {
+                game_path: game_info.path.clone(),
+                version: Box::new(version.clone()),
+            },
+            &version.asset_name,
+            &zip_path,
+            version.file_size,
+        )?;
+        let asset = ReleaseAsset::new(
+            self.release_provider.repo(),
+            &version.version,
+            &version.asset_name,
+            &version.download_url,
+            version.file_size,
+            version.sha256.clone(),
+        );
         
         {
             let progress = progress.clone();
             self.download_service
-                .download_file(
-                    &version.download_url,
+                .for_operation(&progress.operation_id())
+                .download_release_asset(
+                    &asset,
                     zip_path.clone(),
-                    move |downloaded, total| {
-                        if total > 0 {
-                            let percent = 10.0 + (downloaded as f32 / total as f32) * 40.0;
-                            progress.lock().unwrap()("Đang tải xuống...", percent);
-                        }
-                    },
+                    move |downloaded, total| progress.download(downloaded, total),
                 )
-                .await?;
+                .instrument(tracing::info_span!("download"))
+                .await
+                .inspect_err(|e| DownloadQueueService::mark_interrupted(&queue_id, e))?;
         }
+        DownloadQueueService::complete(&queue_id);
 
-        // Giải nén
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang giải nén...", 50.0);
+        let bytes_downloaded = std::fs::metadata(&zip_path).map(|m| m.len()).unwrap_or(0);
+
+        // Kiểm tra chữ ký trước khi giải nén, xóa file nếu không hợp lệ
+        progress.phase_with_file(ProgressPhase::Verifying, Some(&version.asset_name));
+        let signature = self.fetch_signature(version.signature_url.as_deref()).await?;
+        if let Err(e) = SignatureService::check_pack(&zip_path, signature.as_deref()) {
+            FileService::remove_path(&zip_path)?;
+            return Err(e);
         }
+
+        let result = self.install_archive(game_info, &version.version, &zip_path, &temp_dir, bytes_downloaded, progress);
+        FileService::remove_path(&zip_path)?;
+        result
+    }
+
+    /// Tải file chữ ký minisign (nếu release có)
+    async fn fetch_signature(&self, url: Option<&str>) -> Result<Option<String>, AppError> {
+        match url {
+            Some(url) => {
+                let content = self.download_service.fetch_bytes(url).await?;
+                Ok(Some(String::from_utf8_lossy(&content).into_owned()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Cài đặt bản việt hóa từ file nén (zip, 7z, tar.zst) có sẵn trên máy
+    #[tracing::instrument(skip_all, fields(version = %version, zip = %zip_path.display(), game_path = %game_info.path.display()))]
+    pub fn install_local_zip(
+        &self,
+        game_info: &GameInfo,
+        zip_path: &Path,
+        version: &str,
+        progress: &ProgressReporter,
+    ) -> Result<InstallResult, AppError> {
+        tracing::info!("Starting install from local archive");
+        GameService::ensure_game_not_running()?;
+
+        if !zip_path.is_file() {
+            return Err(err!("error.archive_not_found", path = zip_path.display()));
+        }
+
+        // Chữ ký nằm cạnh file nén: <file>.minisig
+        let mut signature_path = zip_path.as_os_str().to_owned();
+        signature_path.push(format!(".{}", SIGNATURE_EXTENSION));
+        let signature = std::fs::read_to_string(&signature_path).ok();
+        SignatureService::check_pack(zip_path, signature.as_deref())?;
+
+        let temp_dir = std::env::temp_dir().join("priconevh_temp");
+        std::fs::create_dir_all(&temp_dir)
+            .map_err(|e| err!("error.create_temp_dir", error = e))?;
+
+        self.install_archive(game_info, version, zip_path, &temp_dir, 0, progress)
+    }
+
+    /// Giải nén và cài đặt bản việt hóa từ file nén đã có, lỗi thì vẫn dọn thư mục tạm
+    fn install_archive(
+        &self,
+        game_info: &GameInfo,
+        version: &str,
+        zip_path: &Path,
+        temp_dir: &Path,
+        bytes_downloaded: u64,
+        progress: &ProgressReporter,
+    ) -> Result<InstallResult, AppError> {
+        let result = self.install_archive_steps(game_info, version, zip_path, temp_dir, bytes_downloaded, progress);
+        if result.is_err() {
+            if let Err(e) = FileService::remove_path(temp_dir) {
+                tracing::warn!(error = %e, "Failed to clean up temp directory");
+            }
+        }
+        result
+    }
+
+    fn install_archive_steps(
+        &self,
+        game_info: &GameInfo,
+        version: &str,
+        zip_path: &Path,
+        temp_dir: &Path,
+        bytes_downloaded: u64,
+        progress: &ProgressReporter,
+    ) -> Result<InstallResult, AppError> {
+        // Bản mới chép đè dxgi.dll và doorstop_config.ini, cần tắt lại nếu người dùng đã tắt
+        let was_disabled = DoorstopService::is_disabled(&game_info.path);
+
+        // Giải nén
+        let archive_name = zip_path.file_name().map(|name| name.to_string_lossy());
+        progress.phase_with_file(ProgressPhase::Extracting, archive_name.as_deref());
+        // Thư mục tạm có thể còn dữ liệu của lần cài lỗi trước, không giải nén chồng lên
         let extract_dir = temp_dir.join("extracted");
-        FileService::extract_zip(&zip_path, &extract_dir)?;
+        FileService::remove_path(&extract_dir)?;
+        let translation_root = tracing::info_span!("extract").in_scope(|| {
+            ArchiveService::extract(zip_path, &extract_dir, |extracted, total| progress.bytes(extracted, total))?;
+            Self::find_translation_root(&extract_dir)
+        })?;
+
+        // Plugin thuộc bản việt hóa mới, dùng để phân biệt với plugin người dùng tự thêm
+        let pack_plugins = PluginService::collect_plugin_files(
+            &translation_root.join("BepInEx").join("plugins")
+        )?;
 
         // Backup files cũ nếu có
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang sao lưu dữ liệu cũ...", 60.0);
-        }
+        progress.phase(ProgressPhase::BackingUp);
         if game_info.has_translation {
-            self.backup_old_translation(&game_info.path)?;
+            tracing::info_span!("backup").in_scope(|| self.backup_old_translation(&game_info.path))?;
         }
 
+        // Giữ lại plugin không thuộc bản việt hóa (bản cài cũ chưa ghi danh sách thì so với bản mới)
+        let known_pack_plugins = TranslationInfo::load(&game_info.path)
+            .map(|info| info.plugins)
+            .filter(|plugins| !plugins.is_empty())
+            .unwrap_or_else(|| pack_plugins.clone());
+        let plugin_stash = temp_dir.join("preserved_plugins");
+        FileService::remove_path(&plugin_stash)?;
+        let user_config_dir = game_info.path.join("BepInEx").join("config");
+        let config_stash = temp_dir.join("user_config");
+        FileService::remove_path(&config_stash)?;
+
+        let preserved_plugins = tracing::info_span!("preserve_user_data").in_scope(|| {
+            let preserved = PluginService::preserve_user_plugins(
+                &game_info.path,
+                &known_pack_plugins,
+                &plugin_stash,
+            )?;
+
+            // Giữ lại config người dùng để merge với config mặc định của bản mới
+            if user_config_dir.exists() {
+                FileService::copy_dir_recursive(&user_config_dir, &config_stash)?;
+            }
+
+            Ok::<_, AppError>(preserved)
+        })?;
+        tracing::info!(
+            user_plugins = preserved_plugins.user_files.len(),
+            disabled_pack_plugins = preserved_plugins.disabled_pack_files.len(),
+            "Preserved user plugins"
+        );
+
         // Xóa files cũ
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang xóa dữ liệu cũ...", 70.0);
-        }
-        self.remove_old_translation(&game_info.path)?;
+        progress.phase(ProgressPhase::RemovingOld);
+        tracing::info_span!("remove_old").in_scope(|| self.remove_old_translation(&game_info.path))?;
 
         // Copy files mới
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang cài đặt bản việt hóa...", 80.0);
+        progress.phase(ProgressPhase::Installing);
+        tracing::info_span!("copy_files").in_scope(|| {
+            self.copy_translation_files(&translation_root, &game_info.path)?;
+            PluginService::restore_user_plugins(&game_info.path, &plugin_stash, &preserved_plugins)
+        })?;
+        if was_disabled {
+            DoorstopService::set_enabled(&game_info.path, false)?;
         }
-        self.copy_translation_files(&extract_dir, &game_info.path)?;
+
+        let defaults_dir = game_info.path.join("translation_defaults").join("config");
+        let config_conflicts = tracing::info_span!("merge_config").in_scope(|| {
+            let conflicts = if config_stash.exists() {
+                ConfigMergeService::merge_config_dir(&config_stash, &user_config_dir, &defaults_dir)?
+            } else {
+                Vec::new()
+            };
+            ConfigMergeService::save_pack_defaults(
+                &translation_root.join("BepInEx").join("config"),
+                &defaults_dir,
+            )?;
+
+            Ok::<_, AppError>(conflicts)
+        })?;
 
         // Tạo file thông tin
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang hoàn tất...", 90.0);
-        }
-        self.create_translation_info(&game_info.path, &version.version)?;
+        progress.phase(ProgressPhase::Finalizing);
+        let (manifest, override_report) = tracing::info_span!("finalize").in_scope(|| {
+            self.create_translation_info(&game_info.path, version, pack_plugins)?;
+
+            // Lưu hash các file để lần cập nhật sau có thể dùng delta
+            let manifest = DeltaService::build_manifest(version, &translation_root)?;
+            DeltaService::save_installed_manifest(&game_info.path, &manifest)?;
+
+            // Áp dụng lại bản sửa của người dùng lên bản việt hóa mới
+            let override_report = OverrideService::apply_overrides(&game_info.path, None)?;
+
+            Ok::<_, AppError>((manifest, override_report))
+        })?;
 
         // Dọn dẹp
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang dọn dẹp...", 95.0);
+        progress.phase(ProgressPhase::CleaningUp);
+        FileService::remove_path(temp_dir)?;
+
+        progress.phase(ProgressPhase::Completed);
+        tracing::info!(
+            files = manifest.files.len(),
+            config_conflicts = config_conflicts.len(),
+            "Full install finished"
+        );
+        Ok(InstallResult {
+            version: version.to_string(),
+            config_conflicts,
+            delta: false,
+            files_updated: manifest.files.len(),
+            bytes_downloaded,
+            obsolete_overrides: override_report.obsolete,
+        })
+    }
+
+    /// Cập nhật bản việt hóa
+    #[tracing::instrument(skip_all, fields(version = %new_version.version, game_path = %game_info.path.display()))]
+    pub async fn update_translation(
+        &self,
+        game_info: &GameInfo,
+        new_version: &TranslationVersion,
+        progress: &ProgressReporter,
+    ) -> Result<InstallResult, AppError> {
+        GameService::ensure_game_not_running()?;
+
+        // Thử cập nhật delta trước, lỗi khi chuẩn bị (chưa đụng tới thư mục game) thì quay về cài đặt toàn bộ
+        let prepared = match self.prepare_delta(game_info, new_version, progress).await {
+            Ok(Some(prepared)) => prepared,
+            Ok(None) => {
+                tracing::info!("Delta update not available, falling back to full install");
+                return self.install_translation(game_info, new_version, progress).await;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Delta update failed, falling back to full install");
+                return self.install_translation(game_info, new_version, progress).await;
+            }
+        };
+
+        // Đã backup và ghi vào thư mục game thì không cài lại toàn bộ (sẽ ghi đè backup),
+        // lỗi thì khôi phục từ backup
+        self.apply_delta(game_info, new_version, prepared, progress)
+    }
+
+    /// Tải các file thay đổi về thư mục tạm, trả về None nếu bản cài hoặc release không hỗ trợ delta
+    #[tracing::instrument(name = "delta_update", skip_all)]
+    async fn prepare_delta(
+        &self,
+        game_info: &GameInfo,
+        new_version: &TranslationVersion,
+        progress: &ProgressReporter,
+    ) -> Result<Option<PreparedDelta>, AppError> {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let manifest_url = match &new_version.manifest_url {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+        // Chỉ zip mới đọc được từng file bằng HTTP Range
+        if ArchiveFormat::from_file_name(&new_version.asset_name).unwrap_or(ArchiveFormat::Zip) != ArchiveFormat::Zip {
+            return Ok(None);
         }
-        FileService::remove_path(&temp_dir)?;
+        let installed = match DeltaService::load_installed_manifest(&game_info.path) {
+            Some(manifest) => manifest,
+            None => return Ok(None),
+        };
 
-        {
+        progress.phase(ProgressPhase::CheckingChanges);
+
+        // Manifest chứa hash từng file nên phải được ký; không ký thì cài lại toàn bộ (kiểm tra chữ ký của file nén)
+        let manifest_content = self.download_service.fetch_bytes(manifest_url).await?;
+        let manifest_signature = self.fetch_signature(new_version.manifest_signature_url.as_deref()).await?;
+        let manifest_verified = match &manifest_signature {
+            Some(signature) => SignatureService::verify_bytes(&manifest_content, signature),
+            None => Err(err!("error.manifest_not_signed")),
+        };
+        if let Err(e) = manifest_verified {
+            if !SignatureService::allow_unsigned() {
+                tracing::warn!(error = %e, "Manifest signature check failed, falling back to full install");
+                return Ok(None);
+            }
+            tracing::warn!(error = %e, "Using unverified manifest (developer mode)");
+        }
+
+        let target: TranslationManifest = serde_json::from_slice(&manifest_content)
+            .map_err(|e| err!("error.parse_url", url = manifest_url, error = e))?;
+        let plan = DeltaService::plan(&installed, &target);
+        tracing::info!(
+            from = %installed.version,
+            to = %target.version,
+            changed = plan.changed.len(),
+            removed = plan.removed.len(),
+            "Planned delta update"
+        );
+
+        let temp_dir = std::env::temp_dir().join("priconevh_delta");
+        if temp_dir.exists() {
+            FileService::remove_path(&temp_dir)?;
+        }
+        let staging_dir = temp_dir.join("staging");
+        std::fs::create_dir_all(&staging_dir)
+            .map_err(|e| err!("error.create_temp_dir", error = e))?;
+
+        progress.phase(ProgressPhase::Downloading);
+
+        let bytes_downloaded = Arc::new(AtomicU64::new(0));
+        let download_result = {
             let progress = progress.clone();
-            progress.lock().unwrap()("Hoàn thành!", 100.0);
+            let bytes_downloaded = bytes_downloaded.clone();
+            DeltaService::download_changed_files(
+                &self.download_service.for_operation(&progress.operation_id()),
+                &new_version.download_url,
+                new_version.file_size,
+                &plan,
+                &staging_dir,
+                move |downloaded, total| {
+                    bytes_downloaded.store(downloaded, Ordering::Relaxed);
+                    progress.download(downloaded, total);
+                },
+            )
+            .instrument(tracing::info_span!("download"))
+            .await
+        };
+
+        if let Err(e) = download_result {
+            FileService::remove_path(&temp_dir)?;
+            return Err(e);
         }
-        Ok(())
+
+        Ok(Some(PreparedDelta {
+            target,
+            plan,
+            temp_dir,
+            staging_dir,
+            bytes_downloaded: bytes_downloaded.load(Ordering::Relaxed),
+        }))
     }
 
-    /// Cập nhật bản việt hóa
-    pub async fn update_translation<F>(
+    /// Backup rồi áp dụng các file đã tải vào thư mục game, lỗi thì khôi phục bản cũ từ backup
+    #[tracing::instrument(name = "delta_apply", skip_all)]
+    fn apply_delta(
         &self,
         game_info: &GameInfo,
         new_version: &TranslationVersion,
-        progress_callback: F,
-    ) -> Result<(), String>
-    where
-        F: FnMut(&str, f32) + Send + 'static,
-    {
-        // Cập nhật giống như cài đặt mới
-        self.install_translation(game_info, new_version, progress_callback).await
+        prepared: PreparedDelta,
+        progress: &ProgressReporter,
+    ) -> Result<InstallResult, AppError> {
+        let PreparedDelta { target, plan, temp_dir, staging_dir, bytes_downloaded } = prepared;
+
+        progress.phase(ProgressPhase::BackingUp);
+        if let Err(e) = tracing::info_span!("backup").in_scope(|| self.backup_old_translation(&game_info.path)) {
+            FileService::remove_path(&temp_dir)?;
+            return Err(e);
+        }
+
+        progress.phase(ProgressPhase::Installing);
+        let was_disabled = DoorstopService::is_disabled(&game_info.path);
+        let result = (|| {
+            let config_conflicts = tracing::info_span!("apply")
+                .in_scope(|| DeltaService::apply(&game_info.path, &staging_dir, &plan))?;
+            if was_disabled {
+                DoorstopService::set_enabled(&game_info.path, false)?;
+            }
+
+            progress.phase(ProgressPhase::Finalizing);
+            let pack_plugins = target.files.iter()
+                .filter_map(|f| f.path.strip_prefix("BepInEx/plugins/"))
+                .map(String::from)
+                .collect();
+            self.create_translation_info(&game_info.path, &new_version.version, pack_plugins)?;
+            DeltaService::save_installed_manifest(&game_info.path, &target)?;
+
+            let fresh_files: Vec<String> = plan.changed.iter().map(|f| f.path.clone()).collect();
+            let override_report = OverrideService::apply_overrides(&game_info.path, Some(&fresh_files))?;
+
+            Ok::<_, AppError>((config_conflicts, override_report))
+        })();
+
+        let (config_conflicts, override_report) = match result {
+            Ok(applied) => applied,
+            Err(e) => {
+                tracing::error!(error = %e, "Delta update failed after backup, restoring previous translation");
+                if let Err(restore_error) = self.restore_translation_backup(&game_info.path) {
+                    tracing::error!(error = %restore_error, "Failed to restore translation backup");
+                }
+                let _ = FileService::remove_path(&temp_dir);
+                return Err(e);
+            }
+        };
+
+        FileService::remove_path(&temp_dir)?;
+
+        progress.phase(ProgressPhase::Completed);
+        tracing::info!(config_conflicts = config_conflicts.len(), "Delta update finished");
+
+        Ok(InstallResult {
+            version: new_version.version.clone(),
+            config_conflicts,
+            delta: true,
+            files_updated: plan.changed.len() + plan.removed.len(),
+            bytes_downloaded,
+            obsolete_overrides: override_report.obsolete,
+        })
     }
 
     /// Gỡ bỏ bản việt hóa
-    pub fn uninstall_translation(&self, game_path: &Path) -> Result<(), String> {
+    #[tracing::instrument(skip(self))]
+    pub fn uninstall_translation(&self, game_path: &Path) -> Result<(), AppError> {
+        GameService::ensure_game_not_running()?;
+
         // Backup trước khi gỡ
         self.backup_old_translation(game_path)?;
 
@@ -151,16 +546,57 @@ impl TranslationService {
             FileService::remove_path(&info_file)?;
         }
 
+        DeltaService::remove_installed_manifest(game_path)?;
+
+        // Xóa config mặc định đã lưu
+        let defaults_dir = game_path.join("translation_defaults");
+        if defaults_dir.exists() {
+            FileService::remove_path(&defaults_dir)?;
+        }
+
         Ok(())
     }
 
+    /// Khôi phục bản việt hóa từ translation_backup, trả về phiên bản đã khôi phục
+    #[tracing::instrument(skip(self))]
+    pub fn restore_translation_backup(&self, game_path: &Path) -> Result<Option<String>, AppError> {
+        let backup_dir = game_path.join("translation_backup");
+        if !backup_dir.is_dir() {
+            return Err(err!("error.no_translation_backup"));
+        }
+        GameService::ensure_game_not_running()?;
+
+        self.remove_old_translation(game_path)?;
+
+        let info_file = game_path.join("translation_info.json");
+        if info_file.exists() {
+            FileService::remove_path(&info_file)?;
+        }
+
+        // Copy lại đúng các mục đã được backup
+        self.copy_translation_files(&backup_dir, game_path)?;
+
+        // Manifest không còn khớp với các file vừa khôi phục, lần cập nhật sau sẽ cài toàn bộ
+        DeltaService::remove_installed_manifest(game_path)?;
+
+        Ok(TranslationInfo::load(game_path).map(|info| info.version))
+    }
+
     /// Kiểm tra cập nhật
-    pub async fn check_for_updates(&self, current_version: &str) -> Result<Option<TranslationVersion>, String> {
-        self.github_service.check_for_updates(current_version).await
+    pub async fn check_for_updates(&self, current_version: &str) -> Result<Option<TranslationVersion>, AppError> {
+        let latest = self.release_provider.get_latest_release().await?;
+        
+        if latest.tag_name != current_version {
+            latest.into_translation_version()
+                .map(Some)
+                .ok_or_else(|| err!("error.no_translation_asset"))
+        } else {
+            Ok(None)
+        }
     }
 
     /// Backup bản việt hóa cũ (CHỈ backup files việt hóa, KHÔNG backup toàn bộ game)
-    fn backup_old_translation(&self, game_path: &Path) -> Result<(), String> {
+    fn backup_old_translation(&self, game_path: &Path) -> Result<(), AppError> {
         // Chỉ backup các thư mục/file liên quan đến việt hóa BepInEx
         let translation_items = vec![
             ("BepInEx", true),  // (path, is_directory)
@@ -168,6 +604,7 @@ impl TranslationService {
             (".doorstop_version", false),
             ("doorstop_config.ini", false),
             ("dxgi.dll", false),
+            ("dxgi.dll.disabled", false),  // Loader đã bị tắt bằng cách đổi tên
             ("translation_info.json", false),
         ];
 
@@ -179,7 +616,7 @@ impl TranslationService {
         }
         
         std::fs::create_dir_all(&backup_dir)
-            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+            .map_err(|e| err!("error.create_backup_dir", error = e))?;
 
         for (item_name, is_dir) in translation_items {
             let item_path = game_path.join(item_name);
@@ -190,7 +627,7 @@ impl TranslationService {
                     FileService::copy_dir_recursive(&item_path, &backup_path)?;
                 } else {
                     std::fs::copy(&item_path, &backup_path)
-                        .map_err(|e| format!("Failed to backup file {}: {}", item_name, e))?;
+                        .map_err(|e| err!("error.backup_file", file = item_name, error = e))?;
                 }
             }
         }
@@ -199,9 +636,9 @@ impl TranslationService {
     }
 
     /// Xóa bản việt hóa cũ
-    fn remove_old_translation(&self, game_path: &Path) -> Result<(), String> {
+    fn remove_old_translation(&self, game_path: &Path) -> Result<(), AppError> {
         let translation_dirs = vec!["BepInEx", "dotnet"];
-        let translation_files = vec![".doorstop_version", "doorstop_config.ini", "dxgi.dll"];
+        let translation_files = vec![".doorstop_version", "doorstop_config.ini", "dxgi.dll", "dxgi.dll.disabled"];
 
         // Xóa thư mục
         for dir_name in translation_dirs {
@@ -222,31 +659,34 @@ impl TranslationService {
         Ok(())
     }
 
-    /// Copy files việt hóa vào game
-    fn copy_translation_files(&self, source: &Path, game_path: &Path) -> Result<(), String> {
-        // Tìm thư mục chứa files việt hóa trong extracted folder
+    /// Tìm thư mục gốc chứa files việt hóa trong extracted folder
+    fn find_translation_root(source: &Path) -> Result<std::path::PathBuf, AppError> {
         // Cấu trúc: PriconneTL_YYYYMMDD-VH/BepInEx/...
-        let translation_root = if source.join("BepInEx").exists() {
-            source.to_path_buf()
-        } else {
-            // Tìm trong các thư mục con (có thể có thư mục wrapper)
-            for entry in std::fs::read_dir(source)
-                .map_err(|e| format!("Failed to read extracted directory: {}", e))? 
-            {
-                let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-                let path = entry.path();
-                if path.is_dir() && path.join("BepInEx").exists() {
-                    return self.copy_translation_files(&path, game_path);
-                }
+        if source.join("BepInEx").exists() {
+            return Ok(source.to_path_buf());
+        }
+
+        // Tìm trong các thư mục con (có thể có thư mục wrapper)
+        for entry in std::fs::read_dir(source)
+            .map_err(|e| err!("error.read_extracted_dir", error = e))? 
+        {
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
+            let path = entry.path();
+            if path.is_dir() && path.join("BepInEx").exists() {
+                return Ok(path);
             }
-            source.to_path_buf()
-        };
+        }
+
+        Ok(source.to_path_buf())
+    }
 
+    /// Copy files việt hóa vào game
+    fn copy_translation_files(&self, translation_root: &Path, game_path: &Path) -> Result<(), AppError> {
         // Copy tất cả files và thư mục
-        for entry in std::fs::read_dir(&translation_root)
-            .map_err(|e| format!("Failed to read translation directory: {}", e))? 
+        for entry in std::fs::read_dir(translation_root)
+            .map_err(|e| err!("error.read_translation_dir", error = e))? 
         {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
             let source_path = entry.path();
             let dest_path = game_path.join(entry.file_name());
 
@@ -254,7 +694,7 @@ impl TranslationService {
                 FileService::copy_dir_recursive(&source_path, &dest_path)?;
             } else {
                 std::fs::copy(&source_path, &dest_path)
-                    .map_err(|e| format!("Failed to copy file: {}", e))?;
+                    .map_err(|e| err!("error.copy_file", error = e))?;
             }
         }
 
@@ -262,7 +702,7 @@ impl TranslationService {
     }
 
     /// Tạo file thông tin việt hóa
-    fn create_translation_info(&self, game_path: &Path, version: &str) -> Result<(), String> {
+    fn create_translation_info(&self, game_path: &Path, version: &str, plugins: Vec<String>) -> Result<(), AppError> {
         let info = TranslationInfo {
             version: version.to_string(),
             installed_date: chrono::Utc::now().to_rfc3339(),
@@ -273,26 +713,82 @@ impl TranslationService {
                 "doorstop_config.ini".to_string(),
                 "dxgi.dll".to_string(),
             ],
+            plugins,
         };
 
         let info_path = game_path.join("translation_info.json");
         let content = serde_json::to_string_pretty(&info)
-            .map_err(|e| format!("Failed to serialize translation info: {}", e))?;
+            .map_err(|e| err!("error.serialize_translation_info", error = e))?;
 
         std::fs::write(info_path, content)
-            .map_err(|e| format!("Failed to write translation info: {}", e))?;
+            .map_err(|e| err!("error.write_translation_info", error = e))?;
 
         Ok(())
     }
 
     /// Lấy thông tin việt hóa hiện tại
     pub fn get_current_translation_info(&self, game_path: &Path) -> Option<TranslationInfo> {
-        let info_path = game_path.join("translation_info.json");
-        if !info_path.exists() {
-            return None;
-        }
+        TranslationInfo::load(game_path)
+    }
+}
 
-        let content = std::fs::read_to_string(info_path).ok()?;
-        serde_json::from_str(&content).ok()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ManifestFile;
+    use crate::services::LocalReleaseService;
+
+    fn manifest_file(path: &str) -> ManifestFile {
+        ManifestFile { path: path.to_string(), size: 0, sha256: String::new() }
+    }
+
+    #[test]
+    fn failed_delta_apply_restores_previous_translation() {
+        let game = tempfile::tempdir().unwrap();
+        let temp = tempfile::tempdir().unwrap();
+        let translation = game.path().join("BepInEx").join("Translation");
+        std::fs::create_dir_all(&translation).unwrap();
+        std::fs::write(translation.join("a.txt"), "cũ a").unwrap();
+        std::fs::write(translation.join("b.txt"), "cũ b").unwrap();
+        std::fs::write(
+            game.path().join("translation_info.json"),
+            r#"{"version":"1.0.0","installed_date":"","files":[]}"#,
+        ).unwrap();
+
+        // Chỉ có a.txt đã tải, b.txt thiếu nên áp dụng lỗi giữa chừng
+        let staging_dir = temp.path().join("staging");
+        std::fs::create_dir_all(staging_dir.join("BepInEx").join("Translation")).unwrap();
+        std::fs::write(staging_dir.join("BepInEx").join("Translation").join("a.txt"), "mới a").unwrap();
+        let plan = DeltaPlan {
+            changed: vec![manifest_file("BepInEx/Translation/a.txt"), manifest_file("BepInEx/Translation/b.txt")],
+            removed: Vec::new(),
+        };
+        let prepared = PreparedDelta {
+            target: TranslationManifest { version: "1.1.0".to_string(), files: plan.changed.clone() },
+            plan,
+            temp_dir: temp.path().to_path_buf(),
+            staging_dir,
+            bytes_downloaded: 0,
+        };
+
+        let service = TranslationService::new(Box::new(LocalReleaseService::new(
+            temp.path().to_path_buf(),
+            "owner/repo".to_string(),
+        )));
+        let game_info = GameInfo::new(game.path().to_path_buf());
+        let version: TranslationVersion = serde_json::from_value(serde_json::json!({
+            "version": "1.1.0",
+            "release_date": "",
+            "download_url": "",
+            "file_size": 0,
+            "changelog": [],
+            "download_count": 0,
+        })).unwrap();
+        let progress = ProgressReporter::new("test", |_| {});
+
+        assert!(service.apply_delta(&game_info, &version, prepared, &progress).is_err());
+        assert_eq!(std::fs::read_to_string(translation.join("a.txt")).unwrap(), "cũ a");
+        assert_eq!(std::fs::read_to_string(translation.join("b.txt")).unwrap(), "cũ b");
+        assert_eq!(TranslationInfo::load(game.path()).unwrap().version, "1.0.0");
     }
 }
@@ -0,0 +1,161 @@
+use crate::err;
+use crate::models::AppError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Một dòng `key=value` trong file text của XUnity.AutoTranslator
+#[derive(Debug, Clone)]
+pub struct TextEntry {
+    pub line: usize,  // Số dòng (bắt đầu từ 1)
+    pub source: String,
+    pub translation: String,
+}
+
+pub struct TranslationTextService;
+
+impl TranslationTextService {
+    /// Thư mục BepInEx/Translation của game
+    pub fn translation_dir(game_path: &Path) -> PathBuf {
+        game_path.join("BepInEx").join("Translation")
+    }
+
+    /// Liệt kê đệ quy các file .txt trong BepInEx/Translation
+    pub fn collect_text_files(game_path: &Path) -> Result<Vec<PathBuf>, AppError> {
+        let mut files = Vec::new();
+        let dir = Self::translation_dir(game_path);
+        if dir.exists() {
+            Self::collect_recursive(&dir, &mut files)?;
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    fn collect_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), AppError> {
+        for entry in fs::read_dir(dir)
+            .map_err(|e| err!("error.read_dir", error = e))?
+        {
+            let entry = entry.map_err(|e| err!("error.read_entry", error = e))?;
+            let path = entry.path();
+
+            if path.is_dir() {
+                Self::collect_recursive(&path, files)?;
+            } else if path.extension().map(|e| e.eq_ignore_ascii_case("txt")).unwrap_or(false) {
+                files.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Đường dẫn tương đối so với thư mục game (dùng '/')
+    pub fn relative_path(game_path: &Path, path: &Path) -> String {
+        let relative = path.strip_prefix(game_path).unwrap_or(path);
+        relative.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Đọc các dòng dịch trong một file (bỏ qua file không phải UTF-8)
+    pub fn read_entries(path: &Path) -> Result<Vec<TextEntry>, AppError> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return Ok(Vec::new()),
+            Err(e) => return Err(err!("error.read_translation_file", error = e)),
+        };
+
+        Ok(content.lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let (source, translation) = Self::parse_line(line)?;
+                Some(TextEntry {
+                    line: index + 1,
+                    source,
+                    translation,
+                })
+            })
+            .collect())
+    }
+
+    /// Tách dòng `key=value` tại dấu '=' đầu tiên không bị escape
+    pub fn parse_line(line: &str) -> Option<(String, String)> {
+        let line = line.trim_start_matches('\u{feff}').trim_end_matches('\r');
+        let index = Self::separator_index(line)?;
+
+        let source = Self::unescape(&line[..index]);
+        let translation = Self::unescape(&line[index + 1..]);
+        if source.is_empty() {
+            None
+        } else {
+            Some((source, translation))
+        }
+    }
+
+    /// Thay bản dịch trong một dòng, giữ nguyên phần key, BOM và '\r' cuối dòng
+    pub fn replace_translation(line: &str, translation: &str) -> Option<String> {
+        let carriage_return = if line.ends_with('\r') { "\r" } else { "" };
+        let content = line.trim_end_matches('\r');
+        let bom_length = content.len() - content.trim_start_matches('\u{feff}').len();
+        let index = bom_length + Self::separator_index(&content[bom_length..])?;
+
+        Some(format!("{}{}{}", &content[..=index], Self::escape(translation), carriage_return))
+    }
+
+    /// Vị trí dấu '=' đầu tiên không bị escape, None nếu là comment hoặc không phải dòng dịch
+    fn separator_index(line: &str) -> Option<usize> {
+        if line.is_empty() || line.starts_with("//") {
+            return None;
+        }
+
+        let mut escaped = false;
+        for (index, c) in line.char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '=' if !escaped => return Some(index),
+                _ => escaped = false,
+            }
+        }
+
+        None
+    }
+
+    /// Tạo dòng `key=value` đã escape
+    pub fn format_line(source: &str, translation: &str) -> String {
+        format!("{}={}", Self::escape(source), Self::escape(translation))
+    }
+
+    fn escape(text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '\\' => result.push_str("\\\\"),
+                '=' => result.push_str("\\="),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                _ => result.push(c),
+            }
+        }
+        result
+    }
+
+    fn unescape(text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut chars = text.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        }
+
+        result
+    }
+}
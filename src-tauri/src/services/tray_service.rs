@@ -0,0 +1,181 @@
+use crate::err;
+use crate::models::AppConfig;
+use crate::services::translation_service::TranslationInfo;
+use crate::services::{DoorstopService, GameService, SchedulerService, TranslationService, UpdaterService};
+use crate::t;
+use std::sync::Arc;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_opener::OpenerExt;
+use tokio::sync::Mutex;
+
+const TRAY_ID: &str = "main";
+
+// Id các thao tác trên menu, nhãn lấy từ key `tray.<id>`
+const MENU_ACTIONS: [&str; 5] = ["show", "check_updates", "update_now", "launch_game", "open_game_folder"];
+
+// Giữ menu item để cập nhật nội dung sau khi cài/gỡ bản việt hóa hoặc đổi ngôn ngữ
+pub struct TrayState {
+    status_item: MenuItem<tauri::Wry>,
+    action_items: Vec<MenuItem<tauri::Wry>>,
+    quit_item: MenuItem<tauri::Wry>,
+}
+
+pub struct TrayService;
+
+impl TrayService {
+    /// Tạo icon khay hệ thống với các thao tác nhanh
+    pub fn setup(
+        app: &AppHandle,
+        translation_service: Arc<Mutex<TranslationService>>,
+        updater_service: Arc<Mutex<UpdaterService>>,
+    ) -> tauri::Result<()> {
+        let status_text = Self::status_text();
+        let status_item = MenuItem::with_id(app, "status", &status_text, false, None::<&str>)?;
+
+        let action_items = MENU_ACTIONS.iter()
+            .map(|id| MenuItem::with_id(app, *id, Self::label(id), true, None::<&str>))
+            .collect::<tauri::Result<Vec<_>>>()?;
+        let quit_item = MenuItem::with_id(app, "quit", Self::label("quit"), true, None::<&str>)?;
+
+        let menu = Menu::with_items(app, &[&status_item, &PredefinedMenuItem::separator(app)?])?;
+        for item in &action_items {
+            menu.append(item)?;
+        }
+        menu.append_items(&[&PredefinedMenuItem::separator(app)?, &quit_item])?;
+
+        let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+            .tooltip(&status_text)
+            .menu(&menu)
+            .show_menu_on_left_click(false)
+            .on_menu_event(move |app, event| {
+                Self::handle_menu_event(app, event, &translation_service, &updater_service);
+            })
+            .on_tray_icon_event(|tray, event| {
+                if let TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } = event
+                {
+                    Self::show_main_window(tray.app_handle());
+                }
+            });
+
+        if let Some(icon) = app.default_window_icon() {
+            builder = builder.icon(icon.clone());
+        }
+
+        builder.build(app)?;
+        app.manage(TrayState { status_item, action_items, quit_item });
+
+        Ok(())
+    }
+
+    /// Cập nhật trạng thái bản việt hóa hiển thị trên khay
+    pub fn refresh_status(app: &AppHandle) {
+        let text = Self::status_text();
+
+        if let Some(state) = app.try_state::<TrayState>() {
+            let _ = state.status_item.set_text(&text);
+        }
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            let _ = tray.set_tooltip(Some(&text));
+        }
+    }
+
+    /// Đặt lại nhãn menu sau khi đổi ngôn ngữ
+    pub fn refresh_labels(app: &AppHandle) {
+        if let Some(state) = app.try_state::<TrayState>() {
+            for item in state.action_items.iter().chain([&state.quit_item]) {
+                let _ = item.set_text(Self::label(item.id().as_ref()));
+            }
+        }
+        Self::refresh_status(app);
+    }
+
+    pub fn show_main_window(app: &AppHandle) {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    }
+
+    fn label(id: &str) -> String {
+        t!(&format!("tray.{}", id))
+    }
+
+    fn status_text() -> String {
+        let config = AppConfig::load().unwrap_or_default();
+
+        match config.game_path {
+            None => t!("tray.status_no_game"),
+            Some(game_path) => match TranslationInfo::load(&game_path) {
+                Some(info) if DoorstopService::is_enabled(&game_path) => {
+                    t!("tray.status_installed", version = info.version)
+                }
+                Some(info) => t!("tray.status_disabled", version = info.version),
+                None => t!("tray.status_not_installed"),
+            },
+        }
+    }
+
+    fn handle_menu_event(
+        app: &AppHandle,
+        event: MenuEvent,
+        translation_service: &Arc<Mutex<TranslationService>>,
+        updater_service: &Arc<Mutex<UpdaterService>>,
+    ) {
+        // Mỗi lần bấm dùng scheduler mới để luôn có thông báo kết quả
+        let scheduler = SchedulerService::new(
+            app.clone(),
+            translation_service.clone(),
+            updater_service.clone(),
+        );
+
+        match event.id().as_ref() {
+            "show" => Self::show_main_window(app),
+            "check_updates" => {
+                tauri::async_runtime::spawn(async move {
+                    let mut scheduler = scheduler;
+                    if !scheduler.check_all(false).await {
+                        scheduler.notify(&t!("notify.no_updates_title"), &t!("notify.no_updates_body"));
+                    }
+                });
+            }
+            "update_now" => {
+                tauri::async_runtime::spawn(async move {
+                    let mut scheduler = scheduler;
+                    if GameService::is_game_running() {
+                        scheduler.notify(&t!("notify.update_blocked_title"), &t!("notify.update_blocked_body"));
+                    } else if !scheduler.check_all(true).await {
+                        scheduler.notify(&t!("notify.no_updates_title"), &t!("notify.no_updates_body"));
+                    }
+                });
+            }
+            "launch_game" => {
+                let result = AppConfig::load().and_then(|config| {
+                    let game_path = config.game_path
+                        .ok_or_else(|| err!("error.game_path_not_set"))?;
+                    GameService::launch_game(&game_path, config.launch_method, &config.launch_args)
+                });
+
+                if let Err(e) = result {
+                    tracing::warn!(error = %e, "Failed to launch game from tray");
+                    scheduler.notify(&t!("notify.launch_failed_title"), e.message());
+                }
+            }
+            "open_game_folder" => {
+                if let Ok(AppConfig { game_path: Some(game_path), .. }) = AppConfig::load() {
+                    if let Err(e) = app.opener().open_path(game_path.to_string_lossy(), None::<&str>) {
+                        tracing::warn!(error = %e, "Failed to open game folder");
+                    }
+                }
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        }
+    }
+}
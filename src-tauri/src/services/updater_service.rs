@@ -1,6 +1,14 @@
-use crate::services::{DownloadService, GitHubService};
+use crate::err;
+use crate::models::{AppConfig, AppError, InstallerKind, PendingUpdate, ProgressPhase, QueuedOperation, ReleaseAsset, SIGNATURE_EXTENSION, UpdateManifest, UpdatePlatform};
+use crate::services::{DownloadQueueService, DownloadService, FileService, ProgressReporter, ReleaseProvider, SignatureService};
+use base64::Engine;
+use minisign_verify::PublicKey;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Tên asset manifest trong release mới nhất (khi không cấu hình update_manifest_url)
+const MANIFEST_ASSET_NAME: &str = "latest.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppUpdateInfo {
@@ -10,121 +18,625 @@ pub struct AppUpdateInfo {
     pub download_url: String,
     pub file_size: u64,
     pub changelog: Vec<String>,
+    #[serde(default)]
+    pub asset_name: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    pub signature: String,
+    pub installer_kind: InstallerKind,
 }
 
 pub struct UpdaterService {
-    github_service: GitHubService,
-    download_service: DownloadService,
+    release_provider: Box<dyn ReleaseProvider>,
     current_version: String,
+    update_keys: Vec<PublicKey>,
 }
 
 impl UpdaterService {
-    pub fn new(github_repo: String, current_version: String) -> Self {
+    pub fn new(release_provider: Box<dyn ReleaseProvider>, current_version: String) -> Self {
         Self {
-            github_service: GitHubService::new(github_repo),
-            download_service: DownloadService::new(),
+            release_provider,
             current_version,
+            update_keys: SignatureService::updater_keys(),
         }
     }
 
+    /// Đổi nguồn release khi thay đổi cài đặt
+    pub fn set_release_provider(&mut self, release_provider: Box<dyn ReleaseProvider>) {
+        self.release_provider = release_provider;
+    }
+
     /// Kiểm tra cập nhật app
-    pub async fn check_for_updates(&self) -> Result<Option<AppUpdateInfo>, String> {
-        let latest = self.github_service.get_latest_release().await?;
-        
-        // So sánh version
-        if self.is_newer_version(&latest.tag_name) {
-            // Chỉ tìm file .msi
-            let asset = latest.assets.iter()
-                .find(|a| a.name.to_lowercase().ends_with(".msi"))
-                .ok_or_else(|| "No MSI installer found in release".to_string())?;
-
-            let changelog: Vec<String> = latest.body
-                .lines()
-                .filter(|line| !line.trim().is_empty())
-                .map(|line| line.trim().to_string())
-                .collect();
-
-            Ok(Some(AppUpdateInfo {
-                version: latest.tag_name,
-                current_version: self.current_version.clone(),
-                release_date: latest.published_at,
-                download_url: asset.browser_download_url.clone(),
-                file_size: asset.size,
-                changelog,
-            }))
-        } else {
-            Ok(None)
+    pub async fn check_for_updates(&self) -> Result<Option<AppUpdateInfo>, AppError> {
+        let (manifest_url, signature_url) = self.manifest_urls().await?;
+        let manifest = self.fetch_manifest(&manifest_url, &signature_url).await?;
+
+        if !Self::is_newer_version(&manifest.version, &self.current_version) {
+            return Ok(None);
+        }
+
+        let (platform_key, platform, installer_kind) = Self::select_platform(&manifest)?;
+        tracing::info!(version = %manifest.version, platform = %platform_key, "App update available");
+
+        let asset_name = platform.url
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        let changelog = manifest.notes
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .collect();
+
+        Ok(Some(AppUpdateInfo {
+            version: manifest.version.clone(),
+            current_version: self.current_version.clone(),
+            release_date: manifest.pub_date.clone(),
+            download_url: platform.url.clone(),
+            file_size: platform.size,
+            changelog,
+            asset_name,
+            sha256: platform.sha256.clone(),
+            signature: platform.signature.clone(),
+            installer_kind,
+        }))
+    }
+
+    /// URL của manifest và chữ ký của nó: URL trong cài đặt (chữ ký ở `<url>.minisig`),
+    /// hoặc asset latest.json và latest.json.minisig của release mới nhất
+    async fn manifest_urls(&self) -> Result<(String, String), AppError> {
+        let configured_url = AppConfig::load()
+            .ok()
+            .and_then(|config| config.update_manifest_url);
+
+        match configured_url {
+            Some(url) => {
+                let signature_url = format!("{}.{}", url, SIGNATURE_EXTENSION);
+                Ok((url, signature_url))
+            }
+            None => self.release_manifest_urls().await,
         }
     }
 
-    /// Tải và cài đặt cập nhật
-    pub async fn download_and_install<F>(
+    async fn release_manifest_urls(&self) -> Result<(String, String), AppError> {
+        let latest = self.release_provider.get_latest_release().await?;
+        let signature_name = format!("{}.{}", MANIFEST_ASSET_NAME, SIGNATURE_EXTENSION);
+        let find_asset = |name: &str| {
+            latest.assets.iter()
+                .find(|a| a.name == name)
+                .map(|a| a.browser_download_url.clone())
+                .ok_or_else(|| err!("error.release_asset_missing", asset = name, release = latest.tag_name))
+        };
+
+        Ok((find_asset(MANIFEST_ASSET_NAME)?, find_asset(&signature_name)?))
+    }
+
+    /// Tải manifest và kiểm tra chữ ký trước khi đọc, để version, URL và hash của file cài đặt
+    /// đều được ký (chữ ký riêng của file cài đặt không che được version trong manifest)
+    async fn fetch_manifest(&self, url: &str, signature_url: &str) -> Result<UpdateManifest, AppError> {
+        let download_service = self.download_service();
+        let content = download_service.fetch_bytes(url).await?;
+        let signature = download_service.fetch_bytes(signature_url)
+            .await
+            .map_err(|e| err!("error.update_manifest_not_signed", error = e))?;
+
+        let signature = Self::decode_signature(&String::from_utf8_lossy(&signature))?;
+        SignatureService::verify_bytes_with(&content, &signature, &self.update_keys)?;
+
+        serde_json::from_slice(&content)
+            .map_err(|e| err!("error.parse_url", url = url, error = e))
+    }
+
+    /// Chỉ đọc file:// trong thư mục của nguồn release hoặc của manifest cập nhật nếu chúng nằm trên máy
+    fn download_service(&self) -> DownloadService {
+        let manifest_dir = AppConfig::load()
+            .ok()
+            .and_then(|config| config.update_manifest_url)
+            .and_then(|url| DownloadService::file_url_dir(&url));
+
+        DownloadService::with_local_roots(
+            self.release_provider.local_root().into_iter().chain(manifest_dir).collect(),
+        )
+    }
+
+    /// Tải, kiểm tra chữ ký và chuẩn bị bản cập nhật để cài khi khởi động lại app
+    #[tracing::instrument(skip_all, fields(version = %update_info.version))]
+    pub async fn download_and_stage(
         &self,
         update_info: &AppUpdateInfo,
-        progress_callback: F,
-    ) -> Result<PathBuf, String>
-    where
-        F: FnMut(&str, f32) + Send + 'static,
-    {
-        use std::sync::{Arc, Mutex};
-        
-        let progress = Arc::new(Mutex::new(progress_callback));
-        
-        {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Đang chuẩn bị...", 0.0);
+        progress: &ProgressReporter,
+    ) -> Result<PendingUpdate, AppError> {
+        progress.phase(ProgressPhase::Preparing);
+
+        // Chỉ giữ một bản cập nhật đang chờ
+        let updates_dir = Self::get_updates_dir()?;
+        if updates_dir.exists() {
+            FileService::remove_path(&updates_dir)?;
         }
+        std::fs::create_dir_all(&updates_dir)
+            .map_err(|e| err!("error.create_update_dir", error = e))?;
 
-        // Tạo thư mục temp
-        let temp_dir = std::env::temp_dir().join("priconevh_update");
-        std::fs::create_dir_all(&temp_dir)
-            .map_err(|e| format!("Failed to create temp directory: {}", e))?;
+        // Tên file lấy từ URL nên chỉ giữ phần tên, không cho chứa thư mục
+        let file_name = Path::new(&update_info.asset_name)
+            .file_name()
+            .filter(|name| name.len() == update_info.asset_name.len())
+            .ok_or_else(|| err!("error.invalid_installer_name", name = update_info.asset_name))?;
+        let installer_path = updates_dir.join(file_name);
 
-        // Xác định tên file
-        let file_name = update_info.download_url
-            .split('/')
-            .last()
-            .unwrap_or("installer.msi");
-        
-        let installer_path = temp_dir.join(file_name);
+        // Tải vào thư mục của hàng đợi để tải tiếp được nếu app bị tắt giữa chừng
+        let download_path = DownloadQueueService::downloads_dir()?.join(file_name);
+        let queue_id = DownloadQueueService::enqueue(
+            QueuedOperation::AppUpdate { version: update_info.version.clone() },
+            &update_info.asset_name,
+            &download_path,
+            update_info.file_size,
+        )?;
+
+        let asset = ReleaseAsset::new(
+            self.release_provider.repo(),
+            &update_info.version,
+            &update_info.asset_name,
+            &update_info.download_url,
+            update_info.file_size,
+            update_info.sha256.clone(),
+        );
 
         // Download installer
+        progress.phase_with_file(ProgressPhase::Downloading, Some(&update_info.asset_name));
+
         {
             let progress = progress.clone();
-            progress.lock().unwrap()("Đang tải xuống bản cập nhật...", 10.0);
-        }
-        
-        {
-            let progress = progress.clone();
-            self.download_service
-                .download_file(
-                    &update_info.download_url,
-                    installer_path.clone(),
-                    move |downloaded, total| {
-                        if total > 0 {
-                            let percent = 10.0 + (downloaded as f32 / total as f32) * 80.0;
-                            progress.lock().unwrap()("Đang tải xuống...", percent);
-                        }
-                    },
+            self.download_service()
+                .for_operation(&progress.operation_id())
+                .download_release_asset(
+                    &asset,
+                    download_path.clone(),
+                    move |downloaded, total| progress.download(downloaded, total),
                 )
-                .await?;
+                .await
+                .inspect_err(|e| DownloadQueueService::mark_interrupted(&queue_id, e))?;
         }
+        DownloadQueueService::complete(&queue_id);
 
+        // Bản cập nhật app luôn phải có chữ ký hợp lệ, kể cả ở chế độ nhà phát triển
+        progress.phase_with_file(ProgressPhase::Verifying, Some(&update_info.asset_name));
+        let verified = Self::decode_signature(&update_info.signature)
+            .and_then(|signature| SignatureService::verify_file_with(&download_path, &signature, &self.update_keys));
+        if let Err(e) = verified {
+            FileService::remove_path(&download_path)?;
+            return Err(e);
+        }
+        std::fs::rename(&download_path, &installer_path)
+            .map_err(|e| err!("error.move_installer", error = e))?;
+
+        let pending = PendingUpdate {
+            version: update_info.version.clone(),
+            installer_kind: update_info.installer_kind,
+            path: installer_path,
+            staged_at: chrono::Utc::now().to_rfc3339(),
+        };
+        Self::save_pending_update(&pending)?;
+        tracing::info!(path = %pending.path.display(), "Staged app update");
+
+        progress.phase(ProgressPhase::Completed);
+
+        Ok(pending)
+    }
+
+    /// Cài bản cập nhật đã chuẩn bị ở lần chạy trước
+    ///
+    /// Trả về true nếu app cần thoát ngay (trình cài đặt đang chạy hoặc đã mở bản mới).
+    #[tracing::instrument]
+    pub fn apply_pending_update(current_version: &str) -> Result<bool, AppError> {
+        let updates_dir = Self::get_updates_dir()?;
+        let Some(pending) = Self::load_pending_update() else {
+            // Dọn file cài đặt của lần cập nhật trước
+            if updates_dir.exists() {
+                FileService::remove_path(&updates_dir)?;
+            }
+            return Ok(false);
+        };
+
+        // Xóa trước khi cài để không lặp lại mãi nếu trình cài đặt lỗi
+        FileService::remove_path(&Self::get_pending_path()?)?;
+
+        if !Self::is_newer_version(&pending.version, current_version) {
+            FileService::remove_path(&updates_dir)?;
+            return Ok(false);
+        }
+        if !pending.path.starts_with(&updates_dir) || !pending.path.is_file() {
+            return Err(err!("error.staged_update_not_found", path = pending.path.display()));
+        }
+
+        tracing::info!(version = %pending.version, kind = ?pending.installer_kind, "Applying staged app update");
+        match pending.installer_kind {
+            InstallerKind::Msi => {
+                Command::new("msiexec")
+                    .arg("/i")
+                    .arg(&pending.path)
+                    .arg("/passive")
+                    .spawn()
+                    .map_err(|e| err!("error.run_installer", error = e))?;
+            }
+            InstallerKind::Nsis => {
+                // /P: cài không cần hỏi, /R: mở lại app sau khi cài
+                Command::new(&pending.path)
+                    .args(["/P", "/R"])
+                    .spawn()
+                    .map_err(|e| err!("error.run_installer", error = e))?;
+            }
+            InstallerKind::AppImage => {
+                let target = Self::replace_appimage(&pending.path)?;
+                Command::new(&target)
+                    .spawn()
+                    .map_err(|e| err!("error.restart_app", error = e))?;
+            }
+            InstallerKind::Dmg => {
+                let bundle = Self::install_dmg(&pending.path)?;
+                Command::new("open")
+                    .arg("-n")
+                    .arg(&bundle)
+                    .spawn()
+                    .map_err(|e| err!("error.restart_app", error = e))?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Ghi đè file AppImage đang chạy (Linux cho phép thay file đang mở bằng rename)
+    fn replace_appimage(installer: &Path) -> Result<PathBuf, AppError> {
+        let target = std::env::var_os("APPIMAGE")
+            .map(PathBuf::from)
+            .ok_or_else(|| err!("error.not_appimage"))?;
+
+        let temp = target.with_extension("new");
+        std::fs::copy(installer, &temp)
+            .map_err(|e| err!("error.copy_update", error = e))?;
+
+        #[cfg(unix)]
         {
-            let progress = progress.clone();
-            progress.lock().unwrap()("Tải xuống hoàn tất!", 100.0);
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&temp, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| err!("error.set_permissions", error = e))?;
+        }
+
+        std::fs::rename(&temp, &target)
+            .map_err(|e| err!("error.replace_appimage", error = e))?;
+        Ok(target)
+    }
+
+    /// Thay bundle .app đang chạy bằng bundle trong file dmg
+    ///
+    /// Chép ra thư mục tạm cạnh bundle rồi đổi tên, lỗi giữa chừng thì bundle cũ vẫn còn nguyên.
+    fn install_dmg(installer: &Path) -> Result<PathBuf, AppError> {
+        // <bundle>.app/Contents/MacOS/<exe>
+        let exe = std::env::current_exe()
+            .map_err(|e| err!("error.current_exe", error = e))?;
+        let bundle = exe.ancestors()
+            .nth(3)
+            .filter(|path| path.extension().is_some_and(|ext| ext == "app"))
+            .ok_or_else(|| err!("error.not_app_bundle"))?
+            .to_path_buf();
+
+        let mount_point = std::env::temp_dir().join("priconevh_update_dmg");
+        std::fs::create_dir_all(&mount_point)
+            .map_err(|e| err!("error.create_mount_point", error = e))?;
+
+        Self::run_command(
+            Command::new("hdiutil")
+                .args(["attach", "-nobrowse", "-readonly", "-mountpoint"])
+                .arg(&mount_point)
+                .arg(installer),
+        )?;
+
+        let result = (|| {
+            let source = std::fs::read_dir(&mount_point)
+                .map_err(|e| err!("error.read_disk_image", error = e))?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+                .ok_or_else(|| err!("error.no_app_bundle"))?;
+
+            let staged = bundle.with_extension("app-update");
+            if staged.exists() {
+                FileService::remove_path(&staged)?;
+            }
+            Self::run_command(Command::new("ditto").arg(&source).arg(&staged))
+                .inspect_err(|_| {
+                    let _ = FileService::remove_path(&staged);
+                })?;
+            Self::swap_bundle(&staged, &bundle)
+        })();
+
+        let _ = Self::run_command(Command::new("hdiutil").arg("detach").arg(&mount_point));
+        result.map(|_| bundle)
+    }
+
+    /// Đổi bundle mới đã chép sẵn vào chỗ bundle cũ, đổi tên lỗi thì trả lại bundle cũ
+    fn swap_bundle(staged: &Path, bundle: &Path) -> Result<(), AppError> {
+        let backup = bundle.with_extension("app-old");
+        if backup.exists() {
+            FileService::remove_path(&backup)?;
+        }
+
+        std::fs::rename(bundle, &backup)
+            .map_err(|e| err!("error.replace_app_bundle", error = e))?;
+        if let Err(e) = std::fs::rename(staged, bundle) {
+            let _ = std::fs::rename(&backup, bundle);
+            let _ = FileService::remove_path(staged);
+            return Err(err!("error.replace_app_bundle", error = e));
+        }
+
+        // Bundle cũ không xóa được thì để lần cập nhật sau dọn
+        if let Err(e) = FileService::remove_path(&backup) {
+            tracing::warn!(path = %backup.display(), error = %e, "Failed to remove old app bundle");
+        }
+        Ok(())
+    }
+
+    fn run_command(command: &mut Command) -> Result<(), AppError> {
+        let program = command.get_program().to_string_lossy().to_string();
+        let status = command.status()
+            .map_err(|e| err!("error.run_command", command = program, error = e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(err!("error.command_failed", command = program, status = status))
         }
+    }
 
-        Ok(installer_path)
+    /// Chọn file cài đặt phù hợp với nền tảng và cách app đang được cài
+    fn select_platform(manifest: &UpdateManifest) -> Result<(String, &UpdatePlatform, InstallerKind), AppError> {
+        let os = match std::env::consts::OS {
+            "macos" => "darwin",
+            os => os,
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86" => "i686",
+            arch => arch,
+        };
+        let base_key = format!("{}-{}", os, arch);
+        let kinds = Self::supported_installer_kinds();
+
+        let mut keys: Vec<String> = kinds.iter()
+            .map(|kind| format!("{}-{}", base_key, kind.platform_suffix()))
+            .collect();
+        keys.push(base_key.clone());
+
+        keys.into_iter()
+            .find_map(|key| {
+                let platform = manifest.platforms.get(&key)?;
+                let kind = InstallerKind::from_file_name(&platform.url)?;
+                kinds.contains(&kind).then_some((key, platform, kind))
+            })
+            .ok_or_else(|| err!("error.no_update_for_platform", platform = base_key))
+    }
+
+    /// Loại file cài đặt dùng được, ưu tiên đúng loại đã cài app
+    fn supported_installer_kinds() -> Vec<InstallerKind> {
+        match std::env::consts::OS {
+            "windows" => {
+                // Bộ cài NSIS đặt uninstall.exe cạnh file exe của app
+                let installed_with_nsis = std::env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.parent().map(|dir| dir.join("uninstall.exe").is_file()))
+                    .unwrap_or(false);
+                if installed_with_nsis {
+                    vec![InstallerKind::Nsis, InstallerKind::Msi]
+                } else {
+                    vec![InstallerKind::Msi, InstallerKind::Nsis]
+                }
+            }
+            "linux" if std::env::var_os("APPIMAGE").is_some() => vec![InstallerKind::AppImage],
+            "macos" => vec![InstallerKind::Dmg],
+            _ => Vec::new(),
+        }
     }
 
-    /// So sánh version (đơn giản)
-    fn is_newer_version(&self, new_version: &str) -> bool {
-        // Loại bỏ 'v' prefix nếu có
-        let current = self.current_version.trim_start_matches('v');
-        let new = new_version.trim_start_matches('v');
-        
-        // So sánh string đơn giản (có thể cải tiến sau)
+    /// Chữ ký có thể là nội dung file .minisig hoặc base64 của nó (định dạng của `tauri signer`)
+    fn decode_signature(signature: &str) -> Result<String, AppError> {
+        let signature = signature.trim();
+        if signature.starts_with("untrusted comment:") {
+            return Ok(signature.to_string());
+        }
+
+        base64::engine::general_purpose::STANDARD
+            .decode(signature)
+            .ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .ok_or_else(|| err!("error.invalid_manifest_signature"))
+    }
+
+    /// Bản cập nhật đang chờ cài (nếu có)
+    pub fn load_pending_update() -> Option<PendingUpdate> {
+        let content = std::fs::read_to_string(Self::get_pending_path().ok()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_pending_update(pending: &PendingUpdate) -> Result<(), AppError> {
+        let content = serde_json::to_string_pretty(pending)
+            .map_err(|e| err!("error.serialize_pending_update", error = e))?;
+        std::fs::write(Self::get_pending_path()?, content)
+            .map_err(|e| err!("error.save_pending_update", error = e))
+    }
+
+    fn get_updates_dir() -> Result<PathBuf, AppError> {
+        Ok(AppConfig::get_data_dir()?.join("updates"))
+    }
+
+    fn get_pending_path() -> Result<PathBuf, AppError> {
+        Ok(AppConfig::get_data_dir()?.join("pending_update.json"))
+    }
+
+    /// So sánh version theo từng số (0.10.0 mới hơn 0.9.1), bỏ qua 'v' và phần sau '-'/'+'
+    fn is_newer_version(new_version: &str, current_version: &str) -> bool {
+        fn parse(version: &str) -> Vec<u64> {
+            version.trim_start_matches('v')
+                .split(['-', '+'])
+                .next()
+                .unwrap_or_default()
+                .split('.')
+                .map_while(|part| part.parse().ok())
+                .collect()
+        }
+
+        let mut new = parse(new_version);
+        let mut current = parse(current_version);
+        if new.is_empty() {
+            return false;
+        }
+
+        let len = new.len().max(current.len());
+        new.resize(len, 0);
+        current.resize(len, 0);
         new > current
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::signature_service::TestKey;
+    use crate::services::ManifestReleaseService;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Server HTTP giả lập nơi phát hành: trả nội dung cố định theo đường dẫn, còn lại 404
+    async fn serve(routes: impl FnOnce(&str) -> Vec<(String, Vec<u8>)>) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let routes = routes(&base);
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buffer = vec![0u8; 4096];
+                let read = stream.read(&mut buffer).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+
+                let response = match routes.iter().find(|(route, _)| route == path) {
+                    Some((_, body)) => [
+                        format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len())
+                            .into_bytes(),
+                        body.clone(),
+                    ].concat(),
+                    None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+                };
+                let _ = stream.write_all(&response).await;
+            }
+        });
+
+        base
+    }
+
+    fn manifest(version: &str) -> Vec<u8> {
+        serde_json::json!({
+            "version": version,
+            "notes": "Sửa lỗi",
+            "pub_date": "2024-01-01T00:00:00Z",
+            "platforms": {
+                "windows-x86_64-msi": {
+                    "url": "https://example.com/priconevh_1.2.0_x64_en-US.msi",
+                    "signature": "",
+                    "size": 1
+                }
+            }
+        }).to_string().into_bytes()
+    }
+
+    // Release mới nhất có latest.json và chữ ký, nội dung trả về có thể khác nội dung đã ký
+    async fn stand_in(served: Vec<u8>, signature: Option<String>) -> UpdaterService {
+        let base = serve(|base| {
+            let releases = serde_json::json!([{
+                "tag_name": "v1.2.0",
+                "published_at": "2024-01-01T00:00:00Z",
+                "assets": [
+                    { "name": "latest.json", "size": 0, "browser_download_url": format!("{}/latest.json", base) },
+                    {
+                        "name": "latest.json.minisig",
+                        "size": 0,
+                        "browser_download_url": format!("{}/latest.json.minisig", base)
+                    }
+                ]
+            }]);
+
+            let mut routes = vec![
+                ("/releases.json".to_string(), releases.to_string().into_bytes()),
+                ("/latest.json".to_string(), served),
+            ];
+            if let Some(signature) = signature {
+                routes.push(("/latest.json.minisig".to_string(), signature.into_bytes()));
+            }
+            routes
+        }).await;
+        let provider = ManifestReleaseService::new(format!("{}/releases.json", base), "owner/repo".to_string());
+
+        UpdaterService {
+            release_provider: Box::new(provider),
+            current_version: "1.0.0".to_string(),
+            update_keys: vec![TestKey::new(2).public_key()],
+        }
+    }
+
+    async fn fetch(updater: &UpdaterService) -> Result<UpdateManifest, AppError> {
+        let (url, signature_url) = updater.release_manifest_urls().await?;
+        updater.fetch_manifest(&url, &signature_url).await
+    }
+
+    #[tokio::test]
+    async fn accepts_manifest_signed_with_updater_key() {
+        let content = manifest("1.2.0");
+        let signature = TestKey::new(2).sign(&content);
+        let updater = stand_in(content, Some(signature)).await;
+
+        let manifest = fetch(&updater).await.unwrap();
+        assert_eq!(manifest.version, "1.2.0");
+        assert!(manifest.platforms.contains_key("windows-x86_64-msi"));
+    }
+
+    #[tokio::test]
+    async fn rejects_manifest_with_changed_version() {
+        let signature = TestKey::new(2).sign(&manifest("1.2.0"));
+        let updater = stand_in(manifest("9.9.9"), Some(signature)).await;
+
+        assert!(fetch(&updater).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_manifest_signed_with_pack_key() {
+        let content = manifest("1.2.0");
+        let signature = TestKey::new(1).sign(&content);
+        let updater = stand_in(content, Some(signature)).await;
+
+        assert!(fetch(&updater).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_unsigned_manifest() {
+        let updater = stand_in(manifest("1.2.0"), None).await;
+
+        assert!(fetch(&updater).await.is_err());
+    }
+
+    #[test]
+    fn swaps_bundle_and_keeps_old_one_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = dir.path().join("PriconeVH.app");
+        let staged = dir.path().join("PriconeVH.app-update");
+        std::fs::create_dir_all(&bundle).unwrap();
+        std::fs::write(bundle.join("version"), "1.0.0").unwrap();
+
+        // Chưa có bundle mới: bundle cũ phải còn nguyên
+        assert!(UpdaterService::swap_bundle(&staged, &bundle).is_err());
+        assert_eq!(std::fs::read_to_string(bundle.join("version")).unwrap(), "1.0.0");
+
+        std::fs::create_dir_all(&staged).unwrap();
+        std::fs::write(staged.join("version"), "1.2.0").unwrap();
+        UpdaterService::swap_bundle(&staged, &bundle).unwrap();
+        assert_eq!(std::fs::read_to_string(bundle.join("version")).unwrap(), "1.2.0");
+        assert!(!staged.exists());
+        assert!(!bundle.with_extension("app-old").exists());
+    }
+}
@@ -1,10 +1,12 @@
 import { useState, useEffect } from "react";
-import { listen } from "@tauri-apps/api/event";
 import { MainScreen } from "./components/pages/MainScreen";
 import { SettingsDialog } from "./components/pages/Settings";
 import { useGamePath } from "./hooks/useGamePath";
 import { useTranslation } from "./hooks/useTranslation";
 import { useAppUpdate } from "./hooks/useAppUpdate";
+import { useDownloadQueue } from "./hooks/useDownloadQueue";
+import { formatBytes, translationApi } from "./lib/api";
+import { overallPercent, phaseLabel, progressDetail } from "./lib/progress";
 import "./App.css";
 
 function App() {
@@ -13,15 +15,20 @@ function App() {
   const gamePathHook = useGamePath();
   const translationHook = useTranslation();
   const appUpdateHook = useAppUpdate();
+  const downloadQueueHook = useDownloadQueue();
 
   useEffect(() => {
     appUpdateHook.checkForUpdates();
+    downloadQueueHook.loadQueue();
   }, []);
 
   useEffect(() => {
-    const unlisten = listen<[string, number]>("translation-progress", (event) => {
-      const [message, progress] = event.payload;
-      translationHook.setProgress({ message, progress });
+    const unlisten = translationApi.onProgress((event) => {
+      translationHook.setProgress({
+        message: phaseLabel(event.phase),
+        progress: overallPercent(event),
+        detail: progressDetail(event),
+      });
     });
     return () => {
       unlisten.then((fn) => fn());
@@ -40,6 +47,48 @@ function App() {
         <SettingsDialog onClose={() => setShowSettings(false)} />
       )}
 
+      {/* Lượt tải dở của lần chạy trước */}
+      {downloadQueueHook.queue.length > 0 && !appUpdateHook.updateInfo && (
+        <div className="confirm-overlay">
+          <div className="update-dialog glass-panel">
+            <h3 className="update-title">Có lượt tải chưa hoàn tất</h3>
+            {downloadQueueHook.queue.map((entry) => (
+              <div key={entry.id} className="update-info">
+                <p className="update-version">
+                  {entry.operation.kind === "translation" ? (
+                    <>Bản việt hóa <strong>{entry.operation.version.version}</strong></>
+                  ) : (
+                    <>Bản cập nhật app <strong>{entry.operation.version}</strong></>
+                  )}
+                </p>
+                <p className="update-current">
+                  Đã tải {formatBytes(entry.downloaded_bytes)} / {formatBytes(entry.total_bytes)}
+                </p>
+                <div className="update-actions">
+                  <button
+                    onClick={() => downloadQueueHook.abandon(entry.id)}
+                    className="update-btn update-cancel"
+                    disabled={downloadQueueHook.resumingId !== null}
+                  >
+                    Bỏ
+                  </button>
+                  <button
+                    onClick={() => downloadQueueHook.resume(entry.id)}
+                    className="update-btn update-confirm"
+                    disabled={downloadQueueHook.resumingId !== null}
+                  >
+                    {downloadQueueHook.resumingId === entry.id ? "Đang tải tiếp..." : "Tải tiếp"}
+                  </button>
+                </div>
+              </div>
+            ))}
+            {downloadQueueHook.error && (
+              <p className="update-error">{downloadQueueHook.error}</p>
+            )}
+          </div>
+        </div>
+      )}
+
       {/* Update Dialog */}
       {appUpdateHook.updateInfo && (
         <div className="confirm-overlay">
@@ -61,6 +110,21 @@ function App() {
                   <div className="progress-fill" style={{ width: `${appUpdateHook.progress}%` }} />
                 </div>
               </div>
+            ) : appUpdateHook.pendingUpdate?.version === appUpdateHook.updateInfo.version ? (
+              <div className="update-actions">
+                <button 
+                  onClick={appUpdateHook.dismissUpdate}
+                  className="update-btn update-cancel"
+                >
+                  Để sau
+                </button>
+                <button 
+                  onClick={appUpdateHook.restartToUpdate}
+                  className="update-btn update-confirm"
+                >
+                  Khởi động lại để cập nhật
+                </button>
+              </div>
             ) : (
               <div className="update-actions">
                 <button 
@@ -77,6 +141,9 @@ function App() {
                 </button>
               </div>
             )}
+            {appUpdateHook.error && (
+              <p className="update-error">{appUpdateHook.error}</p>
+            )}
           </div>
         </div>
       )}
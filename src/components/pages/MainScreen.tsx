@@ -301,6 +301,9 @@ export function MainScreen({ gamePathHook, translationHook, onOpenSettings }: Ma
                 <span className="progress-message">{progress.message}</span>
                 <span className="progress-percent">{Math.round(progress.progress)}%</span>
               </div>
+              {progress.detail && (
+                <div className="progress-detail">{progress.detail}</div>
+              )}
               <div className="progress-bar">
                 <div className="progress-fill" style={{ width: `${progress.progress}%` }} />
               </div>
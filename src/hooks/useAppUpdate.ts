@@ -1,17 +1,35 @@
-import { useState, useCallback } from "react";
+import { useState, useCallback, useEffect } from "react";
 import { updaterApi } from "@/lib/api";
-import type { AppUpdateInfo } from "@/types";
+import type { AppUpdateInfo, PendingUpdate } from "@/types";
 
 export function useAppUpdate() {
   const [updateInfo, setUpdateInfo] = useState<AppUpdateInfo | null>(null);
+  const [pendingUpdate, setPendingUpdate] = useState<PendingUpdate | null>(null);
   const [isDownloading, setIsDownloading] = useState(false);
   const [progress, setProgress] = useState(0);
   const [error, setError] = useState<string | null>(null);
 
+  useEffect(() => {
+    const unlisten = updaterApi.onProgress((event) => {
+      if (event.phase === "completed") {
+        setProgress(100);
+      } else if (event.bytes_total > 0) {
+        setProgress((event.bytes_done / event.bytes_total) * 100);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const checkForUpdates = useCallback(async () => {
     setError(null);
     
     try {
+      // Bản đã tải ở lần trước nhưng chưa khởi động lại
+      const pending = await updaterApi.getPendingUpdate();
+      setPendingUpdate(pending);
+
       const info = await updaterApi.checkUpdate();
       setUpdateInfo(info);
       return info;
@@ -29,25 +47,37 @@ export function useAppUpdate() {
     setError(null);
 
     try {
-      await updaterApi.downloadAndInstall(updateInfo);
+      const pending = await updaterApi.downloadAndInstall(updateInfo);
+      setPendingUpdate(pending);
       setProgress(100);
     } catch (err) {
       setError(err instanceof Error ? err.message : "Tải cập nhật thất bại");
+    } finally {
       setIsDownloading(false);
     }
   }, [updateInfo]);
 
+  const restartToUpdate = useCallback(async () => {
+    try {
+      await updaterApi.restartToUpdate();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Không thể khởi động lại");
+    }
+  }, []);
+
   const dismissUpdate = useCallback(() => {
     setUpdateInfo(null);
   }, []);
 
   return {
     updateInfo,
+    pendingUpdate,
     isDownloading,
     progress,
     error,
     checkForUpdates,
     downloadAndInstall,
+    restartToUpdate,
     dismissUpdate,
   };
 }
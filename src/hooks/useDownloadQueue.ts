@@ -0,0 +1,54 @@
+import { useState, useCallback } from "react";
+import { downloadQueueApi } from "@/lib/api";
+import type { QueuedDownload } from "@/types";
+
+export function useDownloadQueue() {
+  const [queue, setQueue] = useState<QueuedDownload[]>([]);
+  const [resumingId, setResumingId] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadQueue = useCallback(async () => {
+    try {
+      // Lượt tải dở của lần chạy trước
+      const entries = await downloadQueueApi.list();
+      setQueue(entries.filter((entry) => entry.status === "interrupted"));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Không thể đọc danh sách tải dở");
+    }
+  }, []);
+
+  const resume = useCallback(async (id: string) => {
+    setResumingId(id);
+    setError(null);
+
+    try {
+      await downloadQueueApi.resume(id);
+      setQueue((entries) => entries.filter((entry) => entry.id !== id));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Tải tiếp thất bại");
+      await loadQueue();
+    } finally {
+      setResumingId(null);
+    }
+  }, [loadQueue]);
+
+  const abandon = useCallback(async (id: string) => {
+    setError(null);
+
+    try {
+      await downloadQueueApi.abandon(id);
+      setQueue((entries) => entries.filter((entry) => entry.id !== id));
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Không thể xóa file tải dở");
+    }
+  }, []);
+
+  return {
+    queue,
+    resumingId,
+    error,
+    loadQueue,
+    resume,
+    abandon,
+  };
+}
@@ -5,6 +5,7 @@ import type { TranslationPack, TranslationVersion, TranslationInfo } from "@/typ
 interface ProgressState {
   message: string;
   progress: number;
+  detail?: string;  // Dung lượng, tốc độ, thời gian còn lại
 }
 
 export function useTranslation() {
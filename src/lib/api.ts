@@ -5,10 +5,28 @@ import type {
   TranslationPack,
   TranslationVersion,
   TranslationInfo,
+  InstallResult,
   AppConfig,
   AppUpdateInfo,
+  PendingUpdate,
   ProgressEvent,
+  DownloadPausedEvent,
   DiskSpace,
+  PluginInfo,
+  TranslationOverride,
+  OverrideReport,
+  SearchField,
+  SearchMode,
+  TranslationSearchResult,
+  LogEntry,
+  LogLevel,
+  LaunchMethod,
+  ReleaseSource,
+  DownloadMirror,
+  MirrorStat,
+  NetworkConfig,
+  HistoryEntry,
+  QueuedDownload,
 } from "@/types";
 
 // Game API
@@ -20,6 +38,15 @@ export const gameApi = {
   validatePath: (path: string) => invoke<GameInfo>("validate_game_path", { path }),
   
   getInfo: (path: string) => invoke<GameInfo>("get_game_info", { path }),
+  
+  launch: (gamePath?: string) => invoke<void>("launch_game", { gamePath }),
+  
+  isRunning: () => invoke<boolean>("is_game_running"),
+  
+  onStatusChanged: (callback: (running: boolean) => void) =>
+    listen<boolean>("game-status-changed", (event) => {
+      callback(event.payload);
+    }),
 };
 
 // Translation API
@@ -29,24 +56,27 @@ export const translationApi = {
   checkUpdates: (currentVersion: string) =>
     invoke<TranslationVersion | null>("check_translation_updates", { currentVersion }),
   
-  install: (gamePath: string, version: TranslationVersion) =>
-    invoke<void>("install_translation", { gamePath, version }),
+  install: (gamePath: string, version: TranslationVersion, operationId?: string) =>
+    invoke<InstallResult>("install_translation", { gamePath, version, operationId }),
   
-  update: (gamePath: string, newVersion: TranslationVersion) =>
-    invoke<void>("update_translation", { gamePath, newVersion }),
+  update: (gamePath: string, newVersion: TranslationVersion, operationId?: string) =>
+    invoke<InstallResult>("update_translation", { gamePath, newVersion, operationId }),
   
   uninstall: (gamePath: string) =>
     invoke<void>("uninstall_translation", { gamePath }),
   
+  restoreBackup: (gamePath: string) =>
+    invoke<string | null>("restore_translation_backup", { gamePath }),
+  
+  setEnabled: (gamePath: string, enabled: boolean) =>
+    invoke<void>("set_translation_enabled", { gamePath, enabled }),
+  
   getInfo: (gamePath: string) =>
     invoke<TranslationInfo | null>("get_translation_info", { gamePath }),
   
   onProgress: (callback: (event: ProgressEvent) => void) =>
-    listen<[string, number]>("translation-progress", (event) => {
-      callback({
-        message: event.payload[0],
-        progress: event.payload[1],
-      });
+    listen<ProgressEvent>("translation-progress", (event) => {
+      callback(event.payload);
     }),
 };
 
@@ -64,7 +94,44 @@ export const configApi = {
   toggleAutoStart: (enabled: boolean) =>
     invoke<void>("toggle_auto_start", { enabled }),
   
+  toggleCloseToTray: (enabled: boolean) =>
+    invoke<void>("toggle_close_to_tray", { enabled }),
+  
+  setLaunchOptions: (method: LaunchMethod, args: string) =>
+    invoke<void>("set_launch_options", { method, args }),
+  
+  setDeveloperOptions: (developerMode: boolean, allowUnsignedPacks: boolean) =>
+    invoke<void>("set_developer_options", { developerMode, allowUnsignedPacks }),
+  
+  setNetworkConfig: (network: NetworkConfig) =>
+    invoke<void>("set_network_config", { network }),
+  
+  setUpdateManifestUrl: (url: string | null) =>
+    invoke<void>("set_update_manifest_url", { url }),
+  
+  setDownloadMirrors: (mirrors: DownloadMirror[]) =>
+    invoke<void>("set_download_mirrors", { mirrors }),
+  
+  setDownloadRateLimit: (kbps: number) =>
+    invoke<void>("set_download_rate_limit", { kbps }),
+  
+  setDownloadConnections: (connections: number) =>
+    invoke<void>("set_download_connections", { connections }),
+  
+  getMirrorStats: () => invoke<Record<string, MirrorStat>>("get_mirror_stats"),
+  
   setGithubRepo: (repo: string) => invoke<void>("set_github_repo", { repo }),
+  
+  setReleaseSource: (source: ReleaseSource) =>
+    invoke<void>("set_release_source", { source }),
+  
+  setLogLevel: (level: LogLevel) => invoke<void>("set_log_level", { level }),
+  
+  // Ngôn ngữ của thông báo lỗi, hộp thoại và menu khay ("vi" hoặc "en")
+  setLanguage: (language: string) => invoke<void>("set_language", { language }),
+  
+  setUpdateCheckInterval: (hours: number) =>
+    invoke<void>("set_update_check_interval", { hours }),
 };
 
 // System API
@@ -84,24 +151,101 @@ export const systemApi = {
   
   createBackup: (sourcePath: string, backupName: string) =>
     invoke<string>("create_backup", { sourcePath, backupName }),
+  
+  exportDiagnostics: (gamePath: string | null) =>
+    invoke<string | null>("export_diagnostics", { gamePath }),
+  
+  getAppLogs: (level?: LogLevel, query?: string, limit?: number) =>
+    invoke<LogEntry[]>("get_app_logs", { level, query, limit }),
+  
+  pauseDownload: (operationId: string) => invoke<void>("pause_download", { operationId }),
+  
+  resumeDownload: (operationId: string) => invoke<void>("resume_download", { operationId }),
+  
+  isDownloadPaused: (operationId: string) => invoke<boolean>("is_download_paused", { operationId }),
+  
+  onDownloadPausedChanged: (callback: (event: DownloadPausedEvent) => void) =>
+    listen<DownloadPausedEvent>("download-paused-changed", (event) => callback(event.payload)),
 };
 
 // Updater API
 export const updaterApi = {
   checkUpdate: () => invoke<AppUpdateInfo | null>("check_app_update"),
   
-  downloadAndInstall: (updateInfo: AppUpdateInfo) => 
-    invoke<void>("download_and_install_update", { updateInfo }),
+  downloadAndInstall: (updateInfo: AppUpdateInfo, operationId?: string) => 
+    invoke<PendingUpdate>("download_and_install_update", { updateInfo, operationId }),
+  
+  getPendingUpdate: () => invoke<PendingUpdate | null>("get_pending_update"),
+  
+  restartToUpdate: () => invoke<void>("restart_to_update"),
   
   onProgress: (callback: (event: ProgressEvent) => void) =>
-    listen<[string, number]>("updater-progress", (event) => {
-      callback({
-        message: event.payload[0],
-        progress: event.payload[1],
-      });
+    listen<ProgressEvent>("updater-progress", (event) => {
+      callback(event.payload);
+    }),
+  
+  onUpdateAvailable: (callback: (update: AppUpdateInfo) => void) =>
+    listen<AppUpdateInfo>("app-update-available", (event) => {
+      callback(event.payload);
     }),
 };
 
+// Plugin API
+export const pluginApi = {
+  list: (gamePath: string) => invoke<PluginInfo[]>("list_plugins", { gamePath }),
+  
+  setEnabled: (gamePath: string, fileName: string, enabled: boolean) =>
+    invoke<void>("set_plugin_enabled", { gamePath, fileName, enabled }),
+  
+  install: (gamePath: string, zipPath: string) =>
+    invoke<string[]>("install_plugin", { gamePath, zipPath }),
+};
+
+// Override API
+export const overrideApi = {
+  list: () => invoke<TranslationOverride[]>("list_overrides"),
+  
+  add: (gamePath: string | null, source: string, translation: string) =>
+    invoke<void>("add_override", { gamePath, source, translation }),
+  
+  update: (gamePath: string | null, source: string, translation: string) =>
+    invoke<void>("update_override", { gamePath, source, translation }),
+  
+  remove: (gamePath: string | null, source: string) =>
+    invoke<void>("delete_override", { gamePath, source }),
+  
+  apply: (gamePath: string) => invoke<OverrideReport>("apply_overrides", { gamePath }),
+};
+
+// Index API
+export const indexApi = {
+  build: (gamePath: string) => invoke<number>("build_translation_index", { gamePath }),
+  
+  search: (
+    gamePath: string,
+    query: string,
+    field: SearchField = "both",
+    mode: SearchMode = "plain",
+    limit?: number
+  ) => invoke<TranslationSearchResult[]>("search_translations", { gamePath, query, field, mode, limit }),
+};
+
+// History API
+export const historyApi = {
+  list: (limit?: number) => invoke<HistoryEntry[]>("get_history", { limit }),
+  
+  clear: () => invoke<void>("clear_history"),
+};
+
+// Download Queue API
+export const downloadQueueApi = {
+  list: () => invoke<QueuedDownload[]>("get_download_queue"),
+  
+  resume: (id: string) => invoke<void>("resume_queued_download", { id }),
+  
+  abandon: (id: string) => invoke<void>("abandon_download", { id }),
+};
+
 // Helper functions
 export const formatBytes = (bytes: number): string => {
   if (bytes === 0) return "0 Bytes";
@@ -0,0 +1,58 @@
+import type { ProgressEvent, ProgressPhase } from "@/types";
+import { formatBytes } from "@/lib/api";
+
+const PHASE_LABELS: Record<ProgressPhase, string> = {
+  preparing: "Đang chuẩn bị...",
+  checking_changes: "Đang kiểm tra thay đổi...",
+  downloading: "Đang tải xuống...",
+  paused: "Đã tạm dừng tải xuống",
+  verifying: "Đang kiểm tra chữ ký...",
+  extracting: "Đang giải nén...",
+  backing_up: "Đang sao lưu dữ liệu cũ...",
+  removing_old: "Đang xóa dữ liệu cũ...",
+  installing: "Đang cài đặt bản việt hóa...",
+  finalizing: "Đang hoàn tất...",
+  cleaning_up: "Đang dọn dẹp...",
+  completed: "Hoàn thành!",
+};
+
+// Khoảng phần trăm của thanh tiến trình tổng cho từng giai đoạn
+const PHASE_RANGES: Record<ProgressPhase, [number, number]> = {
+  preparing: [0, 5],
+  checking_changes: [5, 10],
+  downloading: [10, 50],
+  paused: [10, 50],
+  verifying: [50, 52],
+  extracting: [52, 60],
+  backing_up: [60, 70],
+  removing_old: [70, 80],
+  installing: [80, 90],
+  finalizing: [90, 95],
+  cleaning_up: [95, 100],
+  completed: [100, 100],
+};
+
+export const phaseLabel = (phase: ProgressPhase): string => PHASE_LABELS[phase];
+
+export const overallPercent = (event: ProgressEvent): number => {
+  const [start, end] = PHASE_RANGES[event.phase];
+  if (event.bytes_total <= 0) return start;
+  return start + (end - start) * Math.min(event.bytes_done / event.bytes_total, 1);
+};
+
+// "12.5 MB / 40 MB · 2.1 MB/s · còn 15 giây"
+export const progressDetail = (event: ProgressEvent): string => {
+  if (event.bytes_total <= 0) return event.current_file ?? "";
+
+  const parts = [`${formatBytes(event.bytes_done)} / ${formatBytes(event.bytes_total)}`];
+  if (event.bytes_per_second > 0) parts.push(`${formatBytes(event.bytes_per_second)}/s`);
+  if (event.eta_secs !== null) parts.push(`còn ${formatDuration(event.eta_secs)}`);
+  return parts.join(" · ");
+};
+
+const formatDuration = (seconds: number): string => {
+  if (seconds < 60) return `${seconds} giây`;
+  const minutes = Math.floor(seconds / 60);
+  if (minutes < 60) return `${minutes} phút ${seconds % 60} giây`;
+  return `${Math.floor(minutes / 60)} giờ ${minutes % 60} phút`;
+};
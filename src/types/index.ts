@@ -5,6 +5,7 @@ export interface GameInfo {
   is_valid: boolean;
   has_translation: boolean;
   translation_version: string | null;
+  translation_enabled: boolean;
 }
 
 // Translation Types
@@ -15,6 +16,11 @@ export interface TranslationVersion {
   file_size: number;
   changelog: string[];
   download_count: number;
+  manifest_url: string | null;
+  signature_url: string | null;  // Chữ ký minisign của asset
+  manifest_signature_url: string | null;
+  asset_name: string;
+  sha256: string | null;
 }
 
 export interface TranslationPack {
@@ -27,6 +33,80 @@ export interface TranslationInfo {
   version: string;
   installed_date: string;
   files: string[];
+  plugins: string[];
+}
+
+export interface ConfigConflict {
+  file: string;
+  section: string;
+  key: string;
+  base_value: string | null;
+  user_value: string;
+  new_value: string;
+}
+
+export interface InstallResult {
+  version: string;
+  config_conflicts: ConfigConflict[];
+  delta: boolean;
+  files_updated: number;
+  bytes_downloaded: number;
+  obsolete_overrides: string[];
+}
+
+// History Types
+export type HistoryOperation = "install" | "update" | "uninstall" | "backup" | "restore";
+
+export interface HistoryEntry {
+  operation: HistoryOperation;
+  profile: string;
+  from_version: string | null;
+  to_version: string | null;
+  started_at: string;
+  duration_ms: number;
+  bytes_downloaded: number;
+  outcome: "success" | "failed";
+  error_code: string | null;
+  error_message: string | null;
+}
+
+// Translation Override Types
+export interface TranslationOverride {
+  source: string;
+  translation: string;
+  pack_translation: string | null;
+  updated_at: string;
+}
+
+export interface OverrideReport {
+  applied: number;
+  appended: number;
+  obsolete: string[];
+}
+
+// Translation Search Types
+export type SearchField = "source" | "translation" | "both";
+
+export type SearchMode = "plain" | "fuzzy" | "regex";
+
+export interface TranslationSearchResult {
+  file: string;
+  line: number;
+  source: string;
+  translation: string;
+  score: number;
+}
+
+// Plugin Types
+export interface PluginInfo {
+  file_name: string;
+  name: string;
+  version: string | null;
+  description: string | null;
+  company: string | null;
+  size: number;
+  enabled: boolean;
+  from_translation_pack: boolean;
 }
 
 // App Config Types
@@ -35,8 +115,85 @@ export interface AppConfig {
   auto_update: boolean;
   auto_start: boolean;
   github_repo: string;
+  release_source: ReleaseSource;
   check_update_on_startup: boolean;
   language: string;
+  log_level: LogLevel;
+  update_check_interval_hours: number;  // 0 = tắt kiểm tra định kỳ
+  close_to_tray: boolean;
+  launch_method: LaunchMethod;
+  launch_args: string;
+  download_mirrors: DownloadMirror[];
+  download_rate_limit_kbps: number;  // 0 = không giới hạn
+  download_connections: number;  // 1-16, 1 = một luồng
+  extract_limits: ExtractLimits;
+  network: NetworkConfig;
+  update_manifest_url: string | null;  // null = latest.json của release mới nhất
+  developer_mode: boolean;
+  allow_unsigned_packs: boolean;  // Chỉ có tác dụng khi bật developer_mode
+}
+
+// Cài đặt mạng cho mọi kết nối (proxy, CA, timeout)
+export interface NetworkConfig {
+  proxy: ProxyConfig;
+  extra_ca_certs: string[];  // File chứng chỉ CA (PEM hoặc DER)
+  connect_timeout_secs: number;
+  read_timeout_secs: number;  // 0 = không giới hạn
+  user_agent: string;  // Rỗng = mặc định
+}
+
+// system: biến môi trường và proxy của hệ điều hành
+export type ProxyConfig =
+  | { mode: "direct" }
+  | { mode: "system" }
+  | {
+      mode: "custom";
+      url: string;  // http://, https://, socks5://, socks5h://
+      username: string | null;
+      password: string | null;
+      no_proxy: string;
+    };
+
+// Giới hạn khi giải nén (chống zip bomb)
+export interface ExtractLimits {
+  max_total_size: number;  // byte
+  max_entries: number;
+  max_ratio: number;
+}
+
+// Mirror tải asset, url_template dùng {repo}, {tag}, {asset}
+export interface DownloadMirror {
+  name: string;
+  url_template: string;
+  enabled: boolean;
+  timeout_secs: number;
+}
+
+export interface MirrorStat {
+  bytes_per_second: number;  // 0 nếu lần gần nhất bị lỗi
+  updated_at: string;
+}
+
+// Nơi lấy danh sách release
+export type ReleaseSource =
+  | { type: "github" }
+  | { type: "gitea"; base_url: string }
+  | { type: "manifest"; url: string }
+  | { type: "local"; path: string };
+
+// dmm: chạy qua DMM Game Player, direct: chạy thẳng file exe
+export type LaunchMethod = "dmm" | "direct";
+
+// Log Types
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error";
+
+export interface LogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+  spans: string[];
 }
 
 // App Update Types
@@ -47,12 +204,70 @@ export interface AppUpdateInfo {
   download_url: string;
   file_size: number;
   changelog: string[];
+  asset_name: string;
+  sha256: string | null;
+  signature: string;
+  installer_kind: InstallerKind;
+}
+
+export type InstallerKind = "msi" | "nsis" | "appimage" | "dmg";
+
+// Bản cập nhật đã tải xong, cài khi khởi động lại app
+export interface PendingUpdate {
+  version: string;
+  installer_kind: InstallerKind;
+  path: string;
+  staged_at: string;
+}
+
+// Lượt tải chưa xong, có thể tải tiếp sau khi khởi động lại app
+export type QueuedOperation =
+  | { kind: "translation"; game_path: string; version: TranslationVersion }
+  | { kind: "app_update"; version: string };
+
+export interface QueuedDownload {
+  id: string;
+  operation: QueuedOperation;
+  asset_name: string;
+  file_path: string;
+  status: "downloading" | "interrupted";
+  downloaded_bytes: number;
+  total_bytes: number;
+  error: string | null;
+  created_at: string;
+  updated_at: string;
 }
 
 // Progress Event Types
+export type ProgressPhase =
+  | "preparing"
+  | "checking_changes"
+  | "downloading"
+  | "paused"
+  | "verifying"
+  | "extracting"
+  | "backing_up"
+  | "removing_old"
+  | "installing"
+  | "finalizing"
+  | "cleaning_up"
+  | "completed";
+
+// Sự kiện tiến trình từ backend, nội dung hiển thị do giao diện tự dịch
 export interface ProgressEvent {
-  message: string;
-  progress: number;
+  operation_id: string;
+  phase: ProgressPhase;
+  bytes_done: number;
+  bytes_total: number;  // 0 = giai đoạn không tính theo byte
+  bytes_per_second: number;
+  eta_secs: number | null;
+  current_file: string | null;
+}
+
+// Sự kiện tạm dừng/tiếp tục tải của một thao tác
+export interface DownloadPausedEvent {
+  operation_id: string;
+  paused: boolean;
 }
 
 // System Types